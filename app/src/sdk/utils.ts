@@ -224,12 +224,21 @@ export function getHtmId(coords: SphericalCoords, depth: number): number {
     return currentHtmId;
 }
 
+// Convert an HTM ID to a trixel ID by reversing its digits. getHtmId lists the
+// base triangle first, trixel IDs list it last so ancestors drop the leading digit
+export function trixelIdFromHtmId(htmId: number): number {
+    return parseInt(htmId.toString().split("").reverse().join(""));
+}
+
 // Get trixel ID from coordinates and resolution
+//
+// Note: this used to return getHtmId's base-first order unchanged, which only
+// matched trixel IDs at resolution 0
 export function getTrixelId(coords: SphericalCoords, resolution: number): number {
     if (resolution > 31) {
         throw new Error("Invalid resolution");
     }
-    return getHtmId(coords, resolution);
+    return trixelIdFromHtmId(getHtmId(coords, resolution));
 }
 
 // Get ancestors for trixel ID
@@ -11,5 +11,37 @@ pub enum ErrorCode {
     InvalidCoordinates,
     InvalidTrixelAccount,
     InvalidTrixelId,
-    ArithmeticOverflow
+    ArithmeticOverflow,
+    CommitRevealRequired,
+    CommitmentMismatch,
+    CommitmentExpired,
+    MissingFeeAccount,
+    InsufficientFunds,
+    MissingReporterAccount,
+    MissingTokenAccount,
+    UpdateTooFrequent,
+    RateLimitExceeded,
+    WorldPaused,
+    TrixelFrozen,
+    ClaimsDisabled,
+    TrixelAlreadyOwned,
+    NotTrixelOwner,
+    InvalidListingKind,
+    ListingHasBids,
+    BidTooLow,
+    AuctionEnded,
+    AuctionNotEnded,
+    MissingUpdateRecord,
+    ChallengeWindowClosed,
+    ChallengeWindowOpen,
+    InvalidDisputeStatus,
+    InsufficientStake,
+    StakeUnbonding,
+    InvalidReturnData,
+    TrixelChanged,
+    OutOfBounds,
+    ResolutionConflict,
+    ResolutionChangeInProgress,
+    CommitmentTooRecent,
+    SubtreeOwned
 }
\ No newline at end of file
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Treasury, World};
+use crate::errors::ErrorCode;
+use super::transfers::{transfer_lamports, transfer_tokens};
+
+/// Accounts needed to collect a world's update fee
+///
+/// All are optional: worlds without a fee schedule need none of them, lamport
+/// fees need only the treasury and token fees need all of them
+#[derive(Accounts)]
+pub struct FeeAccounts<'info> {
+    #[account(mut)]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    pub fee_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub payer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+impl<'info> FeeAccounts<'info> {
+
+    /// Charges `payer` the world's fee for a write at `resolution`, if it has one
+    pub fn collect(
+        &self,
+        world: &Account<'info, World>,
+        payer: &Signer<'info>,
+        system_program: &Program<'info, System>,
+        resolution: u8
+    ) -> Result<()> {
+        let Some(fee_schedule) = world.fee_schedule else {
+            return Ok(());
+        };
+        let amount = fee_schedule.fee_for_resolution(resolution)?;
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let treasury = self.treasury.as_ref().ok_or(ErrorCode::MissingFeeAccount)?;
+        require!(treasury.world == world.key(), ErrorCode::AccountMismatch);
+
+        match fee_schedule.mint {
+            None => transfer_lamports(
+                payer.to_account_info(),
+                treasury.to_account_info(),
+                system_program.to_account_info(),
+                amount
+            ),
+            Some(mint) => {
+                let (Some(fee_mint), Some(payer_token_account), Some(treasury_token_account), Some(token_program)) = (
+                    self.fee_mint.as_ref(),
+                    self.payer_token_account.as_ref(),
+                    self.treasury_token_account.as_ref(),
+                    self.token_program.as_ref()
+                ) else {
+                    return Err(ErrorCode::MissingFeeAccount.into());
+                };
+                require!(fee_mint.key() == mint, ErrorCode::AccountMismatch);
+                require!(
+                    treasury_token_account.owner == treasury.key() && treasury_token_account.mint == mint,
+                    ErrorCode::AccountMismatch
+                );
+
+                transfer_tokens(
+                    payer_token_account.to_account_info(),
+                    treasury_token_account.to_account_info(),
+                    payer.to_account_info(),
+                    fee_mint,
+                    token_program.to_account_info(),
+                    amount,
+                    &[]
+                )
+            }
+        }
+    }
+}
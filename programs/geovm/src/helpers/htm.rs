@@ -261,12 +261,28 @@ pub fn get_htm_id(spherical_coords: SphericalCoords, depth: u32) -> Result<u64>
     Ok(current_htm_id)
 }
 
+// Convert an HTM ID to a trixel ID by reversing its digits. get_htm_id lists the
+// base triangle first, trixel IDs list it last so ancestors drop the leading digit
+pub fn trixel_id_from_htm_id(mut htm_id: u64) -> u64 {
+    let mut id = 0u64;
+    while htm_id > 0 {
+        id = id * 10 + htm_id % 10;
+        htm_id /= 10;
+    }
+    id
+}
+
 // Get trixel ID from coordinates and resolution
+//
+// Note: this used to return get_htm_id's base-first order unchanged, which only
+// matched trixel IDs at resolution 0. It now returns trixel IDs, so update_trixel's
+// coords check accepts the coordinates of deeper trixels instead of rejecting them
 pub fn get_trixel_id(coords: SphericalCoords, resolution: u8) -> Result<u64> {
     if resolution > 31 {
         return Err(error!(ErrorCode::InvalidResolution));
     }
-    get_htm_id(coords, resolution as u32).map_err(|e| error!(ErrorCode::InvalidCoordinates))
+    let htm_id = get_htm_id(coords, resolution as u32).map_err(|_| error!(ErrorCode::InvalidCoordinates))?;
+    Ok(trixel_id_from_htm_id(htm_id))
 }
 
 // Get ancestors for trixel ID
@@ -277,14 +293,14 @@ pub fn get_trixel_ancestors(id: u64) -> Result<Vec<u64>> {
     // Check all digits except last are 1-4
     for digit in id_str.chars().rev().skip(1) {
         let d = digit.to_digit(10).unwrap();
-        if d < 1 || d > 4 {
+        if !(1..=4).contains(&d) {
             return Err(error!(ErrorCode::InvalidTrixelId));
         }
     }
     
     // Check last digit is 1-8
     let last_digit = id_str.chars().last().unwrap().to_digit(10).unwrap();
-    if last_digit < 1 || last_digit > 8 {
+    if !(1..=8).contains(&last_digit) {
         return Err(error!(ErrorCode::InvalidTrixelId));
     }
 
@@ -313,13 +329,18 @@ pub fn get_trixel_pda(trixel_id: u64, world: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(seeds, &crate::ID)
 }
 
-// Verify or create trixel account
-pub fn verify_trixel_account(
+// Verify a trixel account against the bump stored in it, which avoids the
+// search find_program_address does for every trixel of a write
+pub fn verify_trixel_bump(
     trixel_account: &AccountInfo,
     trixel_id: u64,
     world: &Pubkey,
+    bump: u8,
 ) -> Result<()> {
-    let (expected_pda, _) = get_trixel_pda(trixel_id, world);
+    let expected_pda = Pubkey::create_program_address(
+        &[b"trixel", world.as_ref(), trixel_id.to_le_bytes().as_ref(), &[bump]],
+        &crate::ID
+    ).map_err(|_| ErrorCode::InvalidTrixelAccount)?;
     require!(
         trixel_account.key() == expected_pda,
         ErrorCode::InvalidTrixelAccount
@@ -329,7 +350,7 @@ pub fn verify_trixel_account(
 
 // Interpret trixel ID
 pub fn resolution_from_trixel_id(id: u64) -> Result<u8> {
-    if id < 1 || id > 8 {
+    if !(1..=8).contains(&id) {
         // Count digits after the first digit
         let mut count = 0;
         let mut current = id;
@@ -343,6 +364,53 @@ pub fn resolution_from_trixel_id(id: u64) -> Result<u8> {
     }
 }
 
+// Cartesian to Spherical Conversion, RA in [0, 360) and Dec in [-90, 90]
+pub fn cartesian_to_spherical(v: Vector3D) -> SphericalCoords {
+    let v = v_normalize(v);
+    let mut ra = v.y.atan2(v.x) * 180.0 / PI;
+    if ra < 0.0 {
+        ra += 360.0;
+    }
+    let dec = v.z.clamp(-1.0, 1.0).asin() * 180.0 / PI;
+    SphericalCoords { ra, dec }
+}
+
+// Vertices of child `child` (1-4) of a triangle, matching the subdivision in get_htm_id
+pub fn get_child_vertices(vertices: [Vector3D; 3], child: u64) -> Result<[Vector3D; 3]> {
+    let [p0, p1, p2] = vertices;
+    let w0 = v_normalize(v_add(p1, p2));
+    let w1 = v_normalize(v_add(p0, p2));
+    let w2 = v_normalize(v_add(p0, p1));
+    match child {
+        1 => Ok([p0, w2, w1]),
+        2 => Ok([p1, w0, w2]),
+        3 => Ok([p2, w1, w0]),
+        4 => Ok([w0, w1, w2]),
+        _ => Err(error!(ErrorCode::InvalidTrixelId)),
+    }
+}
+
+// Get the vertices of a trixel, walking down from its base triangle (the last
+// digit of the ID) to the trixel itself (the first digit)
+pub fn get_trixel_vertices(id: u64) -> Result<[Vector3D; 3]> {
+    // Validates the ID format
+    get_trixel_ancestors(id)?;
+
+    let mut digits = Vec::new();
+    let mut current = id;
+    while current > 0 {
+        digits.push(current % 10);
+        current /= 10;
+    }
+
+    let base = digits[0];
+    let mut vertices = INITIAL_TRIANGLES[(base - 1) as usize].v;
+    for child in digits.iter().skip(1) {
+        vertices = get_child_vertices(vertices, *child)?;
+    }
+    Ok(vertices)
+}
+
 // Get child index from trixel ID (0-3 for child hashes, 0-7 for base level)
 pub fn get_child_index(id: u64) -> Result<(usize, u8)> {
     if id < 1 {
@@ -365,6 +433,317 @@ pub fn get_child_index(id: u64) -> Result<(usize, u8)> {
     Ok(((current - 1) as usize, resolution))
 }
 
+// Angle in radians between two unit vectors
+fn v_angle(v1: Vector3D, v2: Vector3D) -> f64 {
+    v_length(v_cross(v1, v2)).atan2(v_dot(v1, v2))
+}
+
+// Whether `p`, on the great circle with normal `n` through `a` and `b`, lies on the arc between them
+fn is_on_arc(p: Vector3D, a: Vector3D, b: Vector3D, n: Vector3D) -> bool {
+    v_dot(v_cross(a, p), n) >= -1e-12 && v_dot(v_cross(p, b), n) >= -1e-12
+}
+
+// Angular distance from `p` to the arc from `a` to `b`
+fn arc_point_distance(p: Vector3D, a: Vector3D, b: Vector3D) -> f64 {
+    let n = v_cross(a, b);
+    if v_length(n) > 1e-12 {
+        let n = v_normalize(n);
+        let q = v_subtract(p, v_scale(n, v_dot(p, n)));
+        if v_length(q) > 1e-12 && is_on_arc(v_normalize(q), a, b, n) {
+            return v_dot(p, n).abs().min(1.0).asin();
+        }
+    }
+    v_angle(p, a).min(v_angle(p, b))
+}
+
+// Angular distance between the arcs from `a` to `b` and from `c` to `d`
+fn arc_distance(a: Vector3D, b: Vector3D, c: Vector3D, d: Vector3D) -> f64 {
+    let n1 = v_cross(a, b);
+    let n2 = v_cross(c, d);
+    let line = v_cross(n1, n2);
+    if v_length(line) > 1e-12 {
+        let p = v_normalize(line);
+        for p in [p, v_scale(p, -1.0)] {
+            if is_on_arc(p, a, b, n1) && is_on_arc(p, c, d, n2) {
+                return 0.0;
+            }
+        }
+    }
+    arc_point_distance(a, c, d)
+        .min(arc_point_distance(b, c, d))
+        .min(arc_point_distance(c, a, b))
+        .min(arc_point_distance(d, a, b))
+}
+
+// A great circle segment of a route and the distance around it to cover
+struct PathSegment {
+    a: Vector3D,
+    b: Vector3D,
+    buffer: f64,  // Radians
+}
+
+impl PathSegment {
+    // Whether the buffered segment reaches the triangle with vertices `v`
+    fn touches(&self, v: [Vector3D; 3]) -> bool {
+        if is_point_in_triangle(self.a, v[0], v[1], v[2], 1e-9) || is_point_in_triangle(self.b, v[0], v[1], v[2], 1e-9) {
+            return true;
+        }
+        (0..3).any(|i| arc_distance(self.a, self.b, v[i], v[(i + 1) % 3]) <= self.buffer + 1e-9)
+    }
+
+    // Signed angle along the segment from its start to the projection of `p`
+    fn along(&self, p: Vector3D) -> f64 {
+        let n = v_cross(self.a, self.b);
+        if v_length(n) > 1e-12 {
+            let toward_b = v_normalize(v_cross(n, self.a));
+            v_dot(p, toward_b).atan2(v_dot(p, self.a))
+        } else {
+            v_angle(p, self.a)
+        }
+    }
+
+    // Collects the trixels at `resolution` in the subtree of trixel `id` (at
+    // `depth`, with vertices `v`) that the buffered segment reaches
+    fn cover(&self, id: u64, v: [Vector3D; 3], depth: u8, resolution: u8, hits: &mut Vec<(u64, f64)>) -> Result<()> {
+        if !self.touches(v) {
+            return Ok(());
+        }
+        if depth == resolution {
+            hits.push((id, self.along(v_normalize(v_add(v_add(v[0], v[1]), v[2])))));
+            return Ok(());
+        }
+        for child in 1..=4u64 {
+            let child_id = child * 10u64.pow(depth as u32 + 1) + id;
+            self.cover(child_id, get_child_vertices(v, child)?, depth + 1, resolution, hits)?;
+        }
+        Ok(())
+    }
+}
+
+// Get the trixel IDs at `resolution` within `buffer_deg` degrees of the route
+// following great circles through `points`, in the order the route reaches them
+pub fn cover_path(points: &[SphericalCoords], resolution: u8, buffer_deg: f64) -> Result<Vec<u64>> {
+    // Deeper ids overflow a u64
+    if resolution > MAX_SET_RESOLUTION {
+        return Err(error!(ErrorCode::InvalidResolution));
+    }
+    if points.is_empty() || buffer_deg.is_nan() || buffer_deg < 0.0 {
+        return Err(error!(ErrorCode::InvalidArgument));
+    }
+
+    let vertices = points.iter()
+        .map(|point| spherical_to_cartesian(*point))
+        .collect::<Result<Vec<_>>>()?;
+    // A single point covers the trixels around it
+    let segments: Vec<(Vector3D, Vector3D)> = match vertices.len() {
+        1 => vec![(vertices[0], vertices[0])],
+        _ => vertices.windows(2).map(|pair| (pair[0], pair[1])).collect(),
+    };
+
+    let mut cover = Vec::new();
+    let mut seen = std::collections::BTreeSet::new();
+    for (a, b) in segments {
+        let segment = PathSegment { a, b, buffer: buffer_deg.to_radians() };
+        let mut hits = Vec::new();
+        for base in &INITIAL_TRIANGLES {
+            segment.cover(base.id, base.v, 0, resolution, &mut hits)?;
+        }
+        hits.sort_by(|(_, x), (_, y)| x.total_cmp(y));
+        for (id, _) in hits {
+            if seen.insert(id) {
+                cover.push(id);
+            }
+        }
+    }
+    Ok(cover)
+}
+
+// Deepest resolution a trixel ID fits in a u64 at, and so the resolution trixel sets are kept at
+pub const MAX_SET_RESOLUTION: u8 = 18;
+
+// Number of trixels at MAX_SET_RESOLUTION under each base triangle
+const BASE_SPAN: u64 = 1 << (2 * MAX_SET_RESOLUTION as u64);
+
+// The trixels with nested indices in [start, end) at MAX_SET_RESOLUTION
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrixelRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+// A set of trixels at mixed resolutions, kept as sorted and disjoint ranges of
+// the trixels at MAX_SET_RESOLUTION it covers. Four siblings cover the same
+// range as their parent, so they're always stored as, and listed as, the parent
+#[derive(AnchorSerialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrixelSet {
+    ranges: Vec<TrixelRange>,
+}
+
+impl AnchorDeserialize for TrixelSet {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let ranges = Vec::<TrixelRange>::deserialize_reader(reader)?;
+        TrixelSet::from_ranges(ranges).map_err(|_| std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "trixel set ranges must be sorted, disjoint and non-empty"
+        ))
+    }
+}
+
+// The range of trixels at MAX_SET_RESOLUTION under trixel `id`
+fn trixel_range(id: u64) -> Result<TrixelRange> {
+    get_trixel_ancestors(id)?;
+    let resolution = resolution_from_trixel_id(id)?;
+    if resolution > MAX_SET_RESOLUTION {
+        return Err(error!(ErrorCode::InvalidResolution));
+    }
+
+    // Walk down from the base triangle (the last digit) to the trixel (the first)
+    let mut current = id;
+    let mut nested = 0;
+    while current > 0 {
+        nested = nested * 4 + current % 10 - 1;
+        current /= 10;
+    }
+
+    let shift = 2 * (MAX_SET_RESOLUTION - resolution) as u64;
+    Ok(TrixelRange { start: nested << shift, end: (nested + 1) << shift })
+}
+
+// The trixel ID of nested index `nested` at `resolution`
+fn trixel_id_from_nested(mut nested: u64, resolution: u8) -> u64 {
+    let mut id = 0;
+    for _ in 0..resolution {
+        id = id * 10 + (nested & 3) + 1;
+        nested >>= 2;
+    }
+    id * 10 + nested + 1
+}
+
+impl TrixelSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Builds a set from ranges, which must be sorted, non-empty and neither
+    // overlapping nor touching, as they are when read from ranges()
+    pub fn from_ranges(ranges: Vec<TrixelRange>) -> Result<Self> {
+        for (i, range) in ranges.iter().enumerate() {
+            require!(range.start < range.end && range.end <= 8 * BASE_SPAN, ErrorCode::InvalidArgument);
+            if i > 0 {
+                require!(ranges[i - 1].end < range.start, ErrorCode::InvalidArgument);
+            }
+        }
+        Ok(Self { ranges })
+    }
+
+    pub fn from_ids(ids: &[u64]) -> Result<Self> {
+        let mut set = Self::new();
+        for id in ids {
+            set.insert(*id)?;
+        }
+        Ok(set)
+    }
+
+    pub fn ranges(&self) -> &[TrixelRange] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // Bytes the set takes serialized
+    pub fn serialized_len(&self) -> usize {
+        4 + self.ranges.len() * std::mem::size_of::<TrixelRange>()
+    }
+
+    pub fn insert(&mut self, id: u64) -> Result<()> {
+        let set = Self { ranges: vec![trixel_range(id)?] };
+        *self = self.union(&set);
+        Ok(())
+    }
+
+    // Whether the whole of trixel `id` is in the set
+    pub fn contains(&self, id: u64) -> Result<bool> {
+        let range = trixel_range(id)?;
+        Ok(self.ranges.iter().any(|r| r.start <= range.start && range.end <= r.end))
+    }
+
+    pub fn union(&self, other: &TrixelSet) -> TrixelSet {
+        let mut all: Vec<TrixelRange> = self.ranges.iter().chain(other.ranges.iter()).copied().collect();
+        all.sort_by_key(|range| range.start);
+
+        let mut ranges: Vec<TrixelRange> = Vec::with_capacity(all.len());
+        for range in all {
+            match ranges.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => ranges.push(range),
+            }
+        }
+        TrixelSet { ranges }
+    }
+
+    pub fn intersection(&self, other: &TrixelSet) -> TrixelSet {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(TrixelRange { start, end });
+            }
+            if a.end < b.end { i += 1 } else { j += 1 }
+        }
+        TrixelSet { ranges }
+    }
+
+    pub fn difference(&self, other: &TrixelSet) -> TrixelSet {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for range in self.ranges.iter() {
+            let mut start = range.start;
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                if other.ranges[k].start > start {
+                    ranges.push(TrixelRange { start, end: other.ranges[k].start });
+                }
+                start = start.max(other.ranges[k].end);
+                k += 1;
+            }
+            if start < range.end {
+                ranges.push(TrixelRange { start, end: range.end });
+            }
+        }
+        TrixelSet { ranges }
+    }
+
+    // The fewest trixel IDs covering exactly the set, ordered along the ranges
+    pub fn ids(&self) -> Vec<u64> {
+        let mut ids = Vec::new();
+        for range in self.ranges.iter() {
+            let mut start = range.start;
+            while start < range.end {
+                // Grow the block while it stays aligned and inside the range
+                let mut level = 0;
+                while level < MAX_SET_RESOLUTION
+                    && start % (1 << (2 * (level + 1) as u64)) == 0
+                    && start + (1 << (2 * (level + 1) as u64)) <= range.end
+                {
+                    level += 1;
+                }
+                let resolution = MAX_SET_RESOLUTION - level;
+                ids.push(trixel_id_from_nested(start >> (2 * level as u64), resolution));
+                start += 1 << (2 * level as u64);
+            }
+        }
+        ids
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -394,7 +773,7 @@ mod tests {
     fn test_htm_id_depth_zero() {
         let coords = SphericalCoords { ra: 10.0, dec: -30.0 };
         let result = get_htm_id(coords, 0).unwrap();
-        assert!(result >= 1 && result <= 8); // Should be one of the initial 8 triangles
+        assert!((1..=8).contains(&result)); // Should be one of the initial 8 triangles
     }
 
     #[test]
@@ -423,7 +802,29 @@ mod tests {
         let base = SphericalCoords { ra: 10.0, dec: -30.0 };
         let base_id = get_trixel_id(base, 0).unwrap();
         println!("Base Level (RA: 10°, Dec: -30°): {}", base_id);
-        assert!(base_id >= 1 && base_id <= 8);
+        assert!((1..=8).contains(&base_id));
+    }
+
+    #[test]
+    fn test_trixel_id_from_htm_id() {
+        assert_eq!(trixel_id_from_htm_id(5), 5);
+        assert_eq!(trixel_id_from_htm_id(5213), 3125);
+
+        let coords = SphericalCoords { ra: 123.0, dec: 45.0 };
+        let htm_id = get_htm_id(coords, 6).unwrap();
+        assert_eq!(get_trixel_id(coords, 6).unwrap(), trixel_id_from_htm_id(htm_id));
+    }
+
+    #[test]
+    fn test_trixel_id_matches_ancestors() {
+        let coords = SphericalCoords { ra: 123.0, dec: 45.0 };
+        let id = get_trixel_id(coords, 6).unwrap();
+        let ancestors = get_trixel_ancestors(id).unwrap();
+
+        // Every ancestor is the same point at one resolution lower
+        for (i, ancestor) in ancestors.iter().enumerate() {
+            assert_eq!(*ancestor, get_trixel_id(coords, 5 - i as u8).unwrap());
+        }
     }
 
     #[test]
@@ -555,6 +956,13 @@ mod tests {
             // Verify PDA is deterministic
             let (pda2, _) = get_trixel_pda(trixel_id, &world);
             assert_eq!(pda, pda2);
+
+            // The stored bump checks the address without searching for it
+            let (mut lamports, mut data) = (0, vec![]);
+            let account = AccountInfo::new(&pda, false, false, &mut lamports, &mut data, &crate::ID, false, 0);
+            assert!(verify_trixel_bump(&account, trixel_id, &world, bump).is_ok());
+            assert!(verify_trixel_bump(&account, trixel_id + 10, &world, bump).is_err());
+            assert!(verify_trixel_bump(&account, trixel_id, &world, bump.wrapping_sub(1)).is_err());
         }
     }
 
@@ -664,10 +1072,10 @@ mod tests {
             assert_eq!(res, 0);
         }
         
-        // Test level 1
+        // Test level 1, the leading digit picks the child as the face is last
         let level1_tests = vec![
-            (21, 0, 1), (22, 1, 1), (23, 2, 1), (24, 3, 1),
-            (25, 0, 1), (26, 1, 1), (27, 2, 1), (28, 3, 1),
+            (11, 0, 1), (21, 1, 1), (31, 2, 1), (41, 3, 1),
+            (18, 0, 1), (28, 1, 1), (38, 2, 1), (48, 3, 1),
         ];
         for (id, expected_idx, expected_res) in level1_tests {
             let (idx, res) = get_child_index(id).unwrap();
@@ -678,9 +1086,9 @@ mod tests {
         
         // Test deeper levels
         let deep_tests = vec![
-            (321, 2, 2), (322, 2, 2), (323, 2, 2), (324, 2, 2),
-            (4321, 3, 3), (4322, 3, 3), (4323, 3, 3), (4324, 3, 3),
-            (54321, 4, 4), (54322, 4, 4), (54323, 4, 4), (54324, 4, 4),
+            (121, 0, 2), (221, 1, 2), (321, 2, 2), (421, 3, 2),
+            (1235, 0, 3), (2235, 1, 3), (3235, 2, 3), (4235, 3, 3),
+            (14238, 0, 4), (24238, 1, 4), (34238, 2, 4), (44238, 3, 4),
         ];
         for (id, expected_idx, expected_res) in deep_tests {
             let (idx, res) = get_child_index(id).unwrap();
@@ -694,4 +1102,115 @@ mod tests {
         println!("Invalid ID 0 result: {:?}", result);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_trixel_vertices_contain_point() {
+        let coords = SphericalCoords { ra: 123.0, dec: 45.0 };
+        let point = spherical_to_cartesian(coords).unwrap();
+
+        let trixel_id = get_trixel_id(coords, 6).unwrap();
+
+        let [v0, v1, v2] = get_trixel_vertices(trixel_id).unwrap();
+        assert!(is_point_in_triangle(point, v0, v1, v2, 1e-9));
+
+        // Base triangles are returned as-is
+        let base = get_trixel_vertices(8).unwrap();
+        let back = cartesian_to_spherical(base[0]);
+        assert!((back.ra - 90.0).abs() < 1e-9 && back.dec.abs() < 1e-9);
+
+        assert!(get_trixel_vertices(95).is_err());
+    }
+
+    #[test]
+    fn test_cover_path_contains_route() {
+        let route = [
+            SphericalCoords { ra: 10.0, dec: 20.0 },
+            SphericalCoords { ra: 40.0, dec: 35.0 },
+            SphericalCoords { ra: 80.0, dec: 30.0 },
+        ];
+        let cover = cover_path(&route, 6, 0.0).unwrap();
+
+        // Sample the great circles between the route points
+        for pair in route.windows(2) {
+            let a = spherical_to_cartesian(pair[0]).unwrap();
+            let b = spherical_to_cartesian(pair[1]).unwrap();
+            for i in 0..=200 {
+                let t = i as f64 / 200.0;
+                let p = v_normalize(v_add(v_scale(a, 1.0 - t), v_scale(b, t)));
+                let htm_id = get_trixel_id(cartesian_to_spherical(p), 6).unwrap();
+                assert!(cover.contains(&htm_id), "{} missing from the cover", htm_id);
+            }
+        }
+
+        // Ordered along the route, without repeats
+        assert_eq!(cover[0], get_trixel_id(route[0], 6).unwrap());
+        let mut unique = cover.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), cover.len());
+
+        // A buffer only widens the cover
+        let buffered = cover_path(&route, 6, 1.0).unwrap();
+        assert!(buffered.len() > cover.len());
+        assert!(cover.iter().all(|id| buffered.contains(id)));
+
+        assert!(cover_path(&[], 6, 0.0).is_err());
+        assert!(cover_path(&route, 6, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_cover_path_resolution_limit() {
+        let point = [SphericalCoords { ra: 10.0, dec: 20.0 }];
+        let cover = cover_path(&point, MAX_SET_RESOLUTION, 0.0).unwrap();
+        assert!(cover.contains(&get_trixel_id(point[0], MAX_SET_RESOLUTION).unwrap()));
+
+        for resolution in [MAX_SET_RESOLUTION + 1, 20, 31, 32] {
+            assert!(cover_path(&point, resolution, 0.0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_trixel_set_merges_siblings() {
+        // The four children of trixel 35 collapse into it
+        let set = TrixelSet::from_ids(&[135, 235, 335, 435, 17]).unwrap();
+        assert_eq!(set.ids(), vec![35, 17]);
+        assert!(set.contains(35).unwrap());
+        assert!(set.contains(2135).unwrap());
+        assert!(!set.contains(5).unwrap());
+
+        // A ring of eight base triangles is the whole sphere
+        let sphere = TrixelSet::from_ids(&[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        assert_eq!(sphere.ranges().len(), 1);
+
+        let roundtrip = TrixelSet::try_from_slice(&set.try_to_vec().unwrap()).unwrap();
+        assert_eq!(roundtrip, set);
+        assert_eq!(set.try_to_vec().unwrap().len(), set.serialized_len());
+
+        // Unsorted ranges are rejected when read back
+        let mut bytes = sphere.union(&set).try_to_vec().unwrap();
+        bytes.extend_from_slice(&TrixelRange { start: 0, end: 1 }.try_to_vec().unwrap());
+        bytes[0] += 1;
+        assert!(TrixelSet::try_from_slice(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_trixel_set_operations() {
+        let a = TrixelSet::from_ids(&[35, 7]).unwrap();
+        let b = TrixelSet::from_ids(&[135, 6]).unwrap();
+
+        assert_eq!(a.union(&b).ids(), vec![35, 6, 7]);
+        assert_eq!(a.intersection(&b).ids(), vec![135]);
+        assert_eq!(a.difference(&b).ids(), vec![235, 335, 435, 7]);
+        assert!(a.difference(&a).is_empty());
+
+        // A route's cover compacts to a set holding each of its trixels
+        let route = [
+            SphericalCoords { ra: 10.0, dec: 20.0 },
+            SphericalCoords { ra: 40.0, dec: 35.0 },
+        ];
+        let cover = cover_path(&route, 8, 2.0).unwrap();
+        let set = TrixelSet::from_ids(&cover).unwrap();
+        assert!(set.ids().len() < cover.len());
+        assert!(cover.iter().all(|id| set.contains(*id).unwrap()));
+    }
 } 
\ No newline at end of file
@@ -1,3 +1,13 @@
 pub mod htm;
 
-pub use htm::*; 
\ No newline at end of file
+pub use htm::*;
+
+pub mod transfers;
+
+pub mod fees;
+
+pub mod reporter;
+
+pub mod ownership;
+
+pub mod writes;
\ No newline at end of file
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::{Trixel, World};
+use crate::errors::ErrorCode;
+use super::htm::verify_trixel_bump;
+
+/// The signer behind a trixel write, along with the trixel token (if any) they
+/// have shown they hold
+///
+/// A trixel's `owner` is either a wallet, or the mint of the token representing
+/// it once minted with `mint_trixel_nft`, in which case whoever holds that token
+/// holds the write rights
+pub struct TrixelUpdater {
+    pub key: Pubkey,
+    pub held_mint: Option<Pubkey>,
+}
+
+impl TrixelUpdater {
+
+    pub fn new(key: Pubkey, owner_token_account: Option<&InterfaceAccount<TokenAccount>>) -> Self {
+        let held_mint = owner_token_account
+            .filter(|token_account| token_account.owner == key && token_account.amount > 0)
+            .map(|token_account| token_account.mint);
+        Self { key, held_mint }
+    }
+
+    /// Whether the updater holds the rights recorded in a trixel's `owner`
+    pub fn owns(&self, owner: Option<Pubkey>) -> bool {
+        owner.is_some() && (owner == Some(self.key) || owner == self.held_mint)
+    }
+}
+
+/// Counts a trixel that became owned (or stopped being owned) in the
+/// `owned_descendants` of each of its ancestors, passed closest first, and
+/// returns their owners
+pub fn count_owned_descendant<'info>(
+    world: &Account<'info, World>,
+    id: u64,
+    ancestor_accounts: &'info [AccountInfo<'info>],
+    owned: bool
+) -> Result<Vec<Option<Pubkey>>> {
+    let world_key = world.key();
+    let ancestors = world.trixel_ancestors(id)?;
+    require!(
+        ancestors.len() == ancestor_accounts.len(),
+        ErrorCode::InvalidArgument
+    );
+
+    let mut owners = Vec::with_capacity(ancestors.len());
+    for (ancestor_id, account) in ancestors.iter().zip(ancestor_accounts.iter()) {
+        let ancestor_loader = AccountLoader::<'info, Trixel>::try_from(account)?;
+        let mut ancestor = ancestor_loader.load_mut()?;
+        verify_trixel_bump(account, *ancestor_id, &world_key, ancestor.bump)?;
+        require!(ancestor.world == world_key, ErrorCode::AccountMismatch);
+        ancestor.owned_descendants = if owned {
+            ancestor.owned_descendants.checked_add(1)
+        } else {
+            ancestor.owned_descendants.checked_sub(1)
+        }.ok_or(ErrorCode::ArithmeticOverflow)?;
+        owners.push(ancestor.owner());
+    }
+    Ok(owners)
+}
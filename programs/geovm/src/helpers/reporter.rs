@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Bounty, Reporter, World};
+use crate::errors::ErrorCode;
+use super::transfers::{transfer_tokens, withdraw_lamports};
+
+/// Emitted whenever a write earns a bounty reward
+#[event]
+pub struct BountyPaid {
+    pub bounty: Pubkey,
+    pub reporter: Pubkey,
+    pub trixel_id: u64,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+}
+
+/// The state of a trixel before a write, used to decide bounty eligibility
+#[derive(Clone, Copy, Debug)]
+pub struct PreviousWrite {
+    pub updates: u64,
+    pub last_update: i64,
+}
+
+/// Accounts describing the reporter behind a write
+///
+/// All are optional: the reporter record is only needed to claim bounties or in
+/// rate limited worlds, and the token accounts only for bounties paid in tokens
+#[derive(Accounts)]
+pub struct ReporterAccounts<'info> {
+    #[account(mut)]
+    pub record: Option<Account<'info, Reporter>>,
+
+    #[account(mut)]
+    pub bounty: Option<Account<'info, Bounty>>,
+
+    pub bounty_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub bounty_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub reporter_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+impl<'info> ReporterAccounts<'info> {
+
+    /// Returns the reporter record, checking it belongs to `payer` in `world`
+    pub fn record_for(
+        &mut self,
+        world: &Account<'info, World>,
+        payer: &Signer<'info>
+    ) -> Result<&mut Account<'info, Reporter>> {
+        let record = self.record.as_mut().ok_or(ErrorCode::MissingReporterAccount)?;
+        require!(
+            record.world == world.key() && record.authority == payer.key(),
+            ErrorCode::AccountMismatch
+        );
+        Ok(record)
+    }
+
+    /// Counts a write against the reporter's allowance for the current epoch,
+    /// if the world limits writes per updater
+    pub fn record_write(
+        &mut self,
+        world: &Account<'info, World>,
+        payer: &Signer<'info>
+    ) -> Result<()> {
+        if world.max_updates_per_epoch == 0 {
+            return Ok(());
+        }
+
+        let epoch = Clock::get()?.epoch;
+        let record = self.record_for(world, payer)?;
+        if record.epoch != epoch {
+            record.epoch = epoch;
+            record.epoch_updates = 0;
+        }
+        require!(
+            record.epoch_updates < world.max_updates_per_epoch,
+            ErrorCode::RateLimitExceeded
+        );
+        record.epoch_updates += 1;
+        Ok(())
+    }
+
+    /// Pays the bounty passed with a write to trixel `id`, if the write is eligible
+    /// and the bounty still holds enough to cover its reward
+    pub fn pay_bounty(
+        &mut self,
+        world: &Account<'info, World>,
+        payer: &Signer<'info>,
+        id: u64,
+        resolution: u8,
+        previous: PreviousWrite
+    ) -> Result<()> {
+        if self.bounty.is_none() {
+            return Ok(());
+        }
+        // Payouts are recorded against the reporter, so it must have a record
+        self.record_for(world, payer)?;
+
+        let Some(bounty) = self.bounty.as_ref() else {
+            return Ok(());
+        };
+        require!(bounty.world == world.key(), ErrorCode::AccountMismatch);
+
+        let now = Clock::get()?.unix_timestamp;
+        if !bounty.is_eligible(id, resolution, previous.updates, previous.last_update, now) {
+            return Ok(());
+        }
+
+        let amount = bounty.reward;
+        let bounty_info = bounty.to_account_info();
+        match bounty.mint {
+            None => {
+                let rent_exempt_minimum = Rent::get()?.minimum_balance(bounty_info.data_len());
+                if bounty_info.lamports().saturating_sub(rent_exempt_minimum) < amount {
+                    return Ok(());
+                }
+                withdraw_lamports(&bounty_info, &payer.to_account_info(), amount)?;
+            }
+            Some(mint) => {
+                let (Some(bounty_mint), Some(bounty_token_account), Some(reporter_token_account), Some(token_program)) = (
+                    self.bounty_mint.as_ref(),
+                    self.bounty_token_account.as_ref(),
+                    self.reporter_token_account.as_ref(),
+                    self.token_program.as_ref()
+                ) else {
+                    return Err(ErrorCode::MissingTokenAccount.into());
+                };
+                require!(bounty_mint.key() == mint, ErrorCode::AccountMismatch);
+                require!(
+                    bounty_token_account.owner == bounty.key() && bounty_token_account.mint == mint,
+                    ErrorCode::AccountMismatch
+                );
+                if bounty_token_account.amount < amount {
+                    return Ok(());
+                }
+
+                let bounty_id_bytes = bounty.bounty_id.to_le_bytes();
+                let (_, bounty_bump) = Pubkey::find_program_address(
+                    &[
+                        b"bounty",
+                        bounty.world.as_ref(),
+                        bounty.creator.as_ref(),
+                        bounty_id_bytes.as_ref()
+                    ],
+                    &crate::ID
+                );
+                transfer_tokens(
+                    bounty_token_account.to_account_info(),
+                    reporter_token_account.to_account_info(),
+                    bounty_info,
+                    bounty_mint,
+                    token_program.to_account_info(),
+                    amount,
+                    &[&[
+                        b"bounty",
+                        bounty.world.as_ref(),
+                        bounty.creator.as_ref(),
+                        bounty_id_bytes.as_ref(),
+                        &[bounty_bump],
+                    ]]
+                )?;
+            }
+        }
+
+        let bounty_key = bounty.key();
+        let mint = bounty.mint;
+        let record = self.record_for(world, payer)?;
+        record.bounty_payouts = record.bounty_payouts.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        let bounty = self.bounty.as_mut().ok_or(ErrorCode::InvalidAccount)?;
+        bounty.payouts = bounty.payouts.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(BountyPaid {
+            bounty: bounty_key,
+            reporter: payer.key(),
+            trixel_id: id,
+            mint,
+            amount,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TransferChecked};
+use crate::errors::ErrorCode;
+
+/// Transfers lamports out of a signer's account through the system program
+pub fn transfer_lamports<'info>(
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    amount: u64
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(system_program, system_program::Transfer { from, to }),
+        amount
+    )
+}
+
+/// Moves lamports out of an account owned by this program, leaving it rent exempt
+pub fn withdraw_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(from.data_len());
+    let available = from.lamports().saturating_sub(rent_exempt_minimum);
+    require!(amount <= available, ErrorCode::InsufficientFunds);
+    from.sub_lamports(amount)?;
+    to.add_lamports(amount)?;
+    Ok(())
+}
+
+/// Transfers tokens between two accounts of `mint`, signing with `signer_seeds`
+/// when the authority is one of this program's PDAs
+pub fn transfer_tokens<'info>(
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]]
+) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+        from,
+        mint: mint.to_account_info(),
+        to,
+        authority,
+    };
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds),
+        amount,
+        mint.decimals
+    )
+}
+
+/// Empties a token account held by one of this program's PDAs into `to` and
+/// closes it, returning its rent to `rent_to`
+///
+/// The whole balance is moved rather than just what the program put there, as
+/// anyone can send tokens to the account and closing fails while it holds any
+pub fn close_token_escrow<'info>(
+    escrow: &InterfaceAccount<'info, TokenAccount>,
+    to: Option<AccountInfo<'info>>,
+    mint: Option<&InterfaceAccount<'info, Mint>>,
+    rent_to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]]
+) -> Result<()> {
+    if escrow.amount > 0 {
+        let (Some(to), Some(mint)) = (to, mint) else {
+            return Err(ErrorCode::MissingTokenAccount.into());
+        };
+        transfer_tokens(
+            escrow.to_account_info(),
+            to,
+            authority.clone(),
+            mint,
+            token_program.clone(),
+            escrow.amount,
+            signer_seeds
+        )?;
+    }
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: escrow.to_account_info(),
+            destination: rent_to,
+            authority,
+        },
+        signer_seeds
+    ))
+}
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount};
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::{Stake, Trixel, UpdateRecord, World};
+use crate::state::trixel_data::{RollUp, TrixelData};
+use crate::errors::ErrorCode;
+use super::fees::*;
+use super::ownership::TrixelUpdater;
+use super::reporter::*;
+use super::transfers::transfer_lamports;
+
+/// Accounts shared by the instructions that write a value to a trixel:
+/// update_trixel, reveal_trixel_update, update_at_point and ingest_points
+///
+/// All are optional, each is only needed by worlds using the matching feature
+#[derive(Accounts)]
+pub struct WriteAccounts<'info> {
+    // Worlds with a minimum stake only, the payer's stake
+    pub stake: Option<Account<'info, Stake>>,
+
+    // Proves the payer holds the token of a minted trixel that owns this one
+    pub owner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Worlds with a challenge window only, created by finish_write at
+    /// [b"update", trixel, update_index] to record the write so it can be disputed
+    #[account(mut)]
+    pub update_record: Option<UncheckedAccount<'info>>,
+
+    pub fee: FeeAccounts<'info>,
+
+    pub reporter: ReporterAccounts<'info>,
+}
+
+/// A write apply_trixel_update has just made, as finish_write records it
+pub struct AppliedWrite {
+    pub id: u64,
+    pub resolution: u8,
+    pub value: i32,
+    pub previous: PreviousWrite,
+    pub previous_data: TrixelData,
+    pub roll_up: RollUp,
+}
+
+impl<'info> WriteAccounts<'info> {
+
+    /// The payer, along with the trixel token they have shown they hold
+    pub fn updater(&self, payer: &Signer<'info>) -> TrixelUpdater {
+        TrixelUpdater::new(payer.key(), self.owner_token_account.as_ref())
+    }
+
+    /// Records a write just applied to `trixel` in worlds with a challenge window,
+    /// escrowing the payer's bond on the record, then pays the payer any bounty
+    /// the write earns
+    pub fn finish_write(
+        &mut self,
+        world: &Account<'info, World>,
+        payer: &Signer<'info>,
+        trixel: &AccountLoader<'info, Trixel>,
+        system_program: &Program<'info, System>,
+        write: AppliedWrite
+    ) -> Result<()> {
+        if world.challenge_window > 0 {
+            let record_info = self.update_record.as_ref().ok_or(ErrorCode::MissingUpdateRecord)?;
+            let trixel_key = trixel.key();
+            let index_bytes = write.previous.updates.to_le_bytes();
+            let (pda, bump) = Pubkey::find_program_address(
+                &[b"update", trixel_key.as_ref(), index_bytes.as_ref()],
+                &crate::ID
+            );
+            require!(record_info.key() == pda, ErrorCode::InvalidAccount);
+
+            // The record holds its rent and the bond, returned when it is closed
+            let space = UpdateRecord::bytes();
+            let lamports = Rent::get()?.minimum_balance(space)
+                .checked_add(world.dispute_bond)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            let signer_seeds: &[&[&[u8]]] = &[&[b"update", trixel_key.as_ref(), index_bytes.as_ref(), &[bump]]];
+            let current = record_info.lamports();
+            if current == 0 {
+                system_program::create_account(
+                    CpiContext::new_with_signer(
+                        system_program.to_account_info(),
+                        CreateAccount { from: payer.to_account_info(), to: record_info.to_account_info() },
+                        signer_seeds
+                    ),
+                    lamports,
+                    space as u64,
+                    &crate::ID
+                )?;
+            } else {
+                // Anyone can send lamports to the address ahead of the write, which
+                // create_account would refuse, so it is funded and set up in steps
+                if lamports > current {
+                    transfer_lamports(
+                        payer.to_account_info(),
+                        record_info.to_account_info(),
+                        system_program.to_account_info(),
+                        lamports - current
+                    )?;
+                }
+                system_program::allocate(
+                    CpiContext::new_with_signer(
+                        system_program.to_account_info(),
+                        Allocate { account_to_allocate: record_info.to_account_info() },
+                        signer_seeds
+                    ),
+                    space as u64
+                )?;
+                system_program::assign(
+                    CpiContext::new_with_signer(
+                        system_program.to_account_info(),
+                        Assign { account_to_assign: record_info.to_account_info() },
+                        signer_seeds
+                    ),
+                    &crate::ID
+                )?;
+            }
+
+            let mut record = UpdateRecord::default();
+            record.init(
+                trixel,
+                payer.key(),
+                write.value,
+                write.previous_data,
+                write.roll_up,
+                world.dispute_bond
+            )?;
+            record.try_serialize(&mut &mut record_info.try_borrow_mut_data()?[..])?;
+        } else {
+            require!(self.update_record.is_none(), ErrorCode::InvalidAccount);
+        }
+
+        self.reporter.pay_bounty(
+            world,
+            payer,
+            write.id,
+            write.resolution,
+            write.previous
+        )
+    }
+}
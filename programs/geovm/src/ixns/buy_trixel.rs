@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Listing, Trixel, World};
+use crate::state::listing::ListingKind;
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::{transfer_lamports, transfer_tokens};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BuyTrixelArgs {
+    pub id: u64,
+    pub max_price: u64,  // Guards against the listing being replaced at a higher price
+}
+
+#[derive(Accounts)]
+#[instruction(args: BuyTrixelArgs)]
+pub struct BuyTrixelCtx<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: receives the sale price and the listing's rent, checked against the listing
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump = trixel.load()?.bump
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+
+    #[account(
+        mut,
+        close = seller,
+        has_one = trixel,
+        has_one = seller @ ErrorCode::AccountMismatch,
+        seeds = [b"listing", trixel.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    // Token listings only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub buyer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_buy_trixel(ctx: Context<BuyTrixelCtx>, args: BuyTrixelArgs) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let listing = &accounts.listing;
+    require!(listing.kind == ListingKind::FixedPrice, ErrorCode::InvalidListingKind);
+    require!(listing.price <= args.max_price, ErrorCode::InvalidArgument);
+
+    match listing.mint {
+        None => transfer_lamports(
+            accounts.buyer.to_account_info(),
+            accounts.seller.to_account_info(),
+            accounts.system_program.to_account_info(),
+            listing.price
+        )?,
+        Some(listing_mint) => {
+            let (Some(mint), Some(buyer_token_account), Some(seller_token_account), Some(token_program)) = (
+                accounts.mint.as_ref(),
+                accounts.buyer_token_account.as_ref(),
+                accounts.seller_token_account.as_ref(),
+                accounts.token_program.as_ref()
+            ) else {
+                return Err(ErrorCode::MissingTokenAccount.into());
+            };
+            require!(mint.key() == listing_mint, ErrorCode::AccountMismatch);
+
+            transfer_tokens(
+                buyer_token_account.to_account_info(),
+                seller_token_account.to_account_info(),
+                accounts.buyer.to_account_info(),
+                mint,
+                token_program.to_account_info(),
+                listing.price,
+                &[]
+            )?;
+        }
+    }
+
+    ctx.accounts.trixel.load_mut()?.set_owner(Some(ctx.accounts.buyer.key()));
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Listing, Trixel, World};
+use crate::state::listing::ListingKind;
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::close_token_escrow;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CancelListingArgs {
+    pub id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: CancelListingArgs)]
+pub struct CancelListingCtx<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump = trixel.load()?.bump
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+
+    #[account(
+        mut,
+        close = seller,
+        has_one = trixel,
+        has_one = seller @ ErrorCode::UnauthorizedAction,
+        seeds = [b"listing", trixel.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    // Token auctions only, closed with the listing so the trixel can be listed again
+    #[account(
+        mut,
+        token::authority = listing
+    )]
+    pub listing_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Only when tokens were sent to the auction's escrow, which go to the seller
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn handle_cancel_listing(ctx: Context<CancelListingCtx>, _args: CancelListingArgs) -> Result<()> {
+    let accounts = &ctx.accounts;
+    // Once an auction has a bid the seller is committed to settling it
+    require!(accounts.listing.highest_bidder.is_none(), ErrorCode::ListingHasBids);
+
+    if let (Some(listing_mint), ListingKind::Auction { .. }) = (accounts.listing.mint, accounts.listing.kind) {
+        let (Some(listing_token_account), Some(token_program)) = (
+            accounts.listing_token_account.as_ref(),
+            accounts.token_program.as_ref()
+        ) else {
+            return Err(ErrorCode::MissingTokenAccount.into());
+        };
+        require!(listing_token_account.mint == listing_mint, ErrorCode::AccountMismatch);
+        if let Some(mint) = accounts.mint.as_ref() {
+            require!(mint.key() == listing_mint, ErrorCode::AccountMismatch);
+        }
+        let trixel_key = accounts.trixel.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            trixel_key.as_ref(),
+            &[ctx.bumps.listing],
+        ]];
+        // With no bids, anything in the escrow was sent to it directly
+        close_token_escrow(
+            listing_token_account,
+            accounts.seller_token_account.as_ref().map(|account| account.to_account_info()),
+            accounts.mint.as_ref(),
+            accounts.seller.to_account_info(),
+            accounts.listing.to_account_info(),
+            token_program.to_account_info(),
+            signer_seeds
+        )?;
+    }
+
+    ctx.accounts.trixel.load_mut()?.set_owner(Some(ctx.accounts.listing.owner));
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use crate::state::{Challenge, Trixel, UpdateRecord, World};
+use crate::state::update_record::DisputeStatus;
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::transfer_lamports;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ChallengeUpdateArgs {
+    pub update_index: u64,  // The trixel's update count before the disputed write
+    pub bond: u64,  // Lamports, at least the world's dispute bond
+}
+
+#[derive(Accounts)]
+#[instruction(args: ChallengeUpdateArgs)]
+pub struct ChallengeUpdateCtx<'info> {
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(has_one = world)]
+    pub trixel: AccountLoader<'info, Trixel>,
+
+    #[account(
+        mut,
+        has_one = world,
+        has_one = trixel,
+        seeds = [b"update", trixel.key().as_ref(), args.update_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub update_record: Account<'info, UpdateRecord>,
+
+    #[account(
+        init,
+        payer = challenger,
+        space = Challenge::bytes(),
+        seeds = [b"challenge", update_record.key().as_ref()],
+        bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_challenge_update(ctx: Context<ChallengeUpdateCtx>, args: ChallengeUpdateArgs) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let record = &mut accounts.update_record;
+    require!(record.status == DisputeStatus::Open, ErrorCode::InvalidDisputeStatus);
+    require!(args.bond >= accounts.world.dispute_bond, ErrorCode::InvalidArgument);
+
+    let now = Clock::get()?.unix_timestamp;
+    let challenge_deadline = record.recorded_at
+        .checked_add(accounts.world.challenge_window)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(now <= challenge_deadline, ErrorCode::ChallengeWindowClosed);
+
+    transfer_lamports(
+        accounts.challenger.to_account_info(),
+        accounts.challenge.to_account_info(),
+        accounts.system_program.to_account_info(),
+        args.bond
+    )?;
+
+    let challenge = &mut accounts.challenge;
+    challenge.update_record = record.key();
+    challenge.challenger = accounts.challenger.key();
+    challenge.bond = args.bond;
+    challenge.created_at = now;
+    record.status = DisputeStatus::Challenged;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::{Trixel, World};
+use crate::errors::ErrorCode;
+use crate::helpers::ownership::{count_owned_descendant, TrixelUpdater};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ClaimTrixelArgs {
+    pub id: u64,  // Any resolution, owning a trixel grants write rights over its whole subtree
+}
+
+#[derive(Accounts)]
+#[instruction(args: ClaimTrixelArgs)]
+pub struct ClaimTrixelCtx<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(
+        constraint = world.claims_enabled @ ErrorCode::ClaimsDisabled,
+        constraint = world.authority == payer.key() || !world.permissioned_updates @ ErrorCode::UnauthorizedAction
+    )]
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump = trixel.load()?.bump
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+
+    // Proves the payer holds the token of a minted ancestor
+    pub owner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Claims an unowned trixel for the payer. The trixel's ancestors must be passed
+/// as remaining accounts (closest first) so a claim cannot be carved out of land
+/// already owned by someone else. Ancestors held through their token count as
+/// the payer's when its token account is passed
+///
+/// Owning a trixel grants write rights over its whole subtree, so a trixel with
+/// owned trixels beneath it can't be claimed, or the claim would reach into them.
+/// Ancestors count their owned descendants to tell without walking the subtree
+pub fn handle_claim_trixel<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimTrixelCtx<'info>>,
+    args: ClaimTrixelArgs
+) -> Result<()> {
+    let claimer = ctx.accounts.payer.key();
+    let updater = TrixelUpdater::new(claimer, ctx.accounts.owner_token_account.as_ref());
+    {
+        let trixel = ctx.accounts.trixel.load()?;
+        require!(trixel.owner().is_none(), ErrorCode::TrixelAlreadyOwned);
+        require!(trixel.owned_descendants == 0, ErrorCode::SubtreeOwned);
+    }
+
+    let owners = count_owned_descendant(&ctx.accounts.world, args.id, ctx.remaining_accounts, true)?;
+    for owner in owners {
+        require!(owner.is_none() || updater.owns(owner), ErrorCode::TrixelAlreadyOwned);
+    }
+
+    ctx.accounts.trixel.load_mut()?.set_owner(Some(claimer));
+    Ok(())
+}
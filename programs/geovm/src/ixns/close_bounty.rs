@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+use crate::state::Bounty;
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::transfer_tokens;
+
+#[derive(Accounts)]
+pub struct CloseBountyCtx<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator @ ErrorCode::UnauthorizedAction,
+        seeds = [b"bounty", bounty.world.as_ref(), creator.key().as_ref(), bounty.bounty_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    // Token bounties only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bounty
+    )]
+    pub bounty_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn handle_close_bounty(ctx: Context<CloseBountyCtx>) -> Result<()> {
+    let accounts = &ctx.accounts;
+
+    // Lamport rewards are returned to the creator when the bounty is closed
+    let Some(bounty_mint) = accounts.bounty.mint else {
+        return Ok(());
+    };
+
+    let (Some(mint), Some(bounty_token_account), Some(creator_token_account), Some(token_program)) = (
+        accounts.mint.as_ref(),
+        accounts.bounty_token_account.as_ref(),
+        accounts.creator_token_account.as_ref(),
+        accounts.token_program.as_ref()
+    ) else {
+        return Err(ErrorCode::MissingTokenAccount.into());
+    };
+    require!(mint.key() == bounty_mint, ErrorCode::AccountMismatch);
+
+    let bounty_id_bytes = accounts.bounty.bounty_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"bounty",
+        accounts.bounty.world.as_ref(),
+        accounts.bounty.creator.as_ref(),
+        bounty_id_bytes.as_ref(),
+        &[ctx.bumps.bounty],
+    ]];
+
+    transfer_tokens(
+        bounty_token_account.to_account_info(),
+        creator_token_account.to_account_info(),
+        accounts.bounty.to_account_info(),
+        mint,
+        token_program.to_account_info(),
+        bounty_token_account.amount,
+        signer_seeds
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: bounty_token_account.to_account_info(),
+            destination: accounts.creator.to_account_info(),
+            authority: accounts.bounty.to_account_info(),
+        },
+        signer_seeds
+    ))
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::state::{UpdateRecord, World};
+use crate::state::update_record::DisputeStatus;
+use crate::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct CloseUpdateRecordCtx<'info> {
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        close = updater,
+        has_one = world,
+        has_one = updater @ ErrorCode::UnauthorizedAction,
+        seeds = [b"update", update_record.trixel.as_ref(), update_record.update_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub update_record: Account<'info, UpdateRecord>,
+}
+
+/// Returns the updater's bond once their write can no longer be challenged
+pub fn handle_close_update_record(ctx: Context<CloseUpdateRecordCtx>) -> Result<()> {
+    let record = &ctx.accounts.update_record;
+    match record.status {
+        DisputeStatus::Challenged => Err(ErrorCode::InvalidDisputeStatus.into()),
+        DisputeStatus::Settled => Ok(()),
+        DisputeStatus::Open => {
+            let challenge_deadline = record.recorded_at
+                .checked_add(ctx.accounts.world.challenge_window)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            require!(
+                Clock::get()?.unix_timestamp > challenge_deadline,
+                ErrorCode::ChallengeWindowOpen
+            );
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::state::{Stake, TrixelCommitment, World};
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CommitTrixelUpdateArgs {
+    pub hash: [u8;32],  // TrixelCommitment::compute_hash(world, payer, id, value, salt)
+}
+
+#[derive(Accounts)]
+#[instruction(args: CommitTrixelUpdateArgs)]
+pub struct CommitTrixelUpdateCtx<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        constraint = world.authority == payer.key() || !world.permissioned_updates @ ErrorCode::UnauthorizedAction,
+        constraint = world.authority == payer.key() || Stake::covers(stake.as_ref(), &world, payer.key()) @ ErrorCode::InsufficientStake,
+        constraint = world.reveal_window > 0 @ ErrorCode::InvalidAccount
+    )]
+    pub world: Account<'info, World>,
+
+    // Worlds with a minimum stake only, the payer's stake
+    pub stake: Option<Account<'info, Stake>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TrixelCommitment::bytes(),
+        seeds = [b"commitment", world.key().as_ref(), payer.key().as_ref(), args.hash.as_ref()],
+        bump
+    )]
+    pub commitment: Account<'info, TrixelCommitment>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_commit_trixel_update(ctx: Context<CommitTrixelUpdateCtx>, args: CommitTrixelUpdateArgs) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    commitment.init(
+        ctx.accounts.world.key(),
+        ctx.accounts.payer.key(),
+        args.hash
+    )
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::World;
+use crate::state::bounty::{Bounty, BountyTarget, BountyTrigger};
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CreateBountyArgs {
+    pub bounty_id: u64,  // Chosen by the creator to tell their bounties apart
+    pub target: BountyTarget,
+    pub trigger: BountyTrigger,
+    pub reward: u64,  // Paid per eligible write, in lamports or tokens of `mint`
+}
+
+#[derive(Accounts)]
+#[instruction(args: CreateBountyArgs)]
+pub struct CreateBountyCtx<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = Bounty::bytes(),
+        seeds = [b"bounty", world.key().as_ref(), creator.key().as_ref(), args.bounty_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    // Token bounties only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = mint,
+        associated_token::authority = bounty,
+        associated_token::token_program = token_program
+    )]
+    pub bounty_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_bounty(ctx: Context<CreateBountyCtx>, args: CreateBountyArgs) -> Result<()> {
+    require!(args.reward > 0, ErrorCode::InvalidArgument);
+    if let BountyTrigger::Cooldown { seconds } = args.trigger {
+        require!(seconds > 0, ErrorCode::InvalidArgument);
+    }
+    let mint = ctx.accounts.mint.as_ref().map(|mint| mint.key());
+    if mint.is_some() {
+        require!(ctx.accounts.bounty_token_account.is_some(), ErrorCode::MissingTokenAccount);
+    }
+
+    let bounty = &mut ctx.accounts.bounty;
+    bounty.world = ctx.accounts.world.key();
+    bounty.creator = ctx.accounts.creator.key();
+    bounty.bounty_id = args.bounty_id;
+    bounty.target = args.target;
+    bounty.trigger = args.trigger;
+    bounty.mint = mint;
+    bounty.reward = args.reward;
+    bounty.payouts = 0;
+    Ok(())
+}
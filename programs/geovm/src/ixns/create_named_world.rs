@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::World;
+use super::create_world::CreateWorldArgs;
+
+#[derive(Accounts)]
+#[instruction(args: CreateWorldArgs)]
+pub struct CreateNamedWorldCtx<'info> {
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Signs so nobody else can take a name under their key
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = World::bytes(),
+        seeds = [b"world", authority.key().as_ref(), args.name.as_ref()],
+        bump
+    )]
+    pub world: Account<'info, World>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates a world at the PDA of its authority and name, so it can be found from
+/// the name alone and each authority has at most one world of a given name
+pub fn handle_create_named_world(ctx: Context<CreateNamedWorldCtx>, args: CreateWorldArgs) -> Result<()> {
+    let world_key = ctx.accounts.world.key();
+    ctx.accounts.world.init(
+        &world_key,
+        ctx.accounts.authority.key(),
+        args.name,
+        args.canonical_resolution,
+        args.permissioned_updates,
+        args.data_type
+    )
+}
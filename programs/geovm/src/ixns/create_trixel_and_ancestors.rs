@@ -2,7 +2,8 @@ use anchor_lang::prelude::*;
 use crate::helpers::get_child_index;
 use crate::state::{Trixel, World};
 use crate::errors::ErrorCode;
-use crate::helpers::htm::{get_trixel_ancestors, resolution_from_trixel_id};
+use crate::helpers::fees::*;
+use crate::helpers::htm::{resolution_from_trixel_id, verify_trixel_bump};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct CreateTrixelAndAncestorsArgs {
@@ -27,37 +28,55 @@ pub struct CreateTrixelAndAncestorsCtx<'info> {
         seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
         bump
     )]
-    pub trixel: Account<'info, Trixel>,
+    pub trixel: AccountLoader<'info, Trixel>,
 
     /// CHECK: This is the system program
     pub system_program: Program<'info, System>,
+
+    pub fee: FeeAccounts<'info>,
 }
 
 
 pub fn handle_create_trixel_and_ancestors<'info>(ctx: Context<'_, '_, 'info, 'info, CreateTrixelAndAncestorsCtx<'info>>, args: CreateTrixelAndAncestorsArgs) -> Result<()> {
-    // Check that the resolution of the trixel is the world's canonical resolution
+    // Check that the world accepts writes at the trixel's resolution
     let trixel_resolution = resolution_from_trixel_id(args.id)?;
-    require!(
-        trixel_resolution == ctx.accounts.world.canonical_resolution,
-        ErrorCode::InvalidResolution
-    );
+    ctx.accounts.world.check_resolution(trixel_resolution)?;
+    ctx.accounts.world.check_bounds(args.id)?;
     let world_data_type = ctx.accounts.world.data.to_data_type();
+    require!(!ctx.accounts.world.paused, ErrorCode::WorldPaused);
+    require!(ctx.accounts.world.resolution_change.is_none(), ErrorCode::ResolutionChangeInProgress);
+    // New trixels have no discriminator until the account is written back
+    let is_new = ctx.accounts.trixel.to_account_info().try_borrow_data()?[..8] == [0u8; 8];
+    require!(is_new || !ctx.accounts.trixel.load()?.is_frozen(), ErrorCode::TrixelFrozen);
+
+    ctx.accounts.fee.collect(
+        &ctx.accounts.world,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        trixel_resolution
+    )?;
 
 
     // Initialize the main trixel
-    let trixel = &mut ctx.accounts.trixel;
-    trixel.init(
-        ctx.accounts.world.key(),
-        args.id,
-        trixel_resolution,
-        world_data_type
-    )?;
+    let mut prev_hash = if is_new {
+        let mut trixel = ctx.accounts.trixel.load_init()?;
+        trixel.init(
+            ctx.accounts.world.key(),
+            args.id,
+            trixel_resolution,
+            ctx.bumps.trixel,
+            ctx.accounts.world.hash_version,
+            world_data_type
+        )?;
+        trixel.hash
+    } else {
+        ctx.accounts.trixel.load()?.hash
+    };
     // Get the child index of this trixel within its parent
-    let (mut prev_child_idx, mut prev_resolution) = get_child_index(args.id)?;
-    let mut prev_hash = ctx.accounts.trixel.hash;
+    let (mut prev_child_idx, _) = get_child_index(args.id)?;
     
     // Derive the list of ancestors of the trixel
-    let ancestors = get_trixel_ancestors(args.id)?;
+    let ancestors = ctx.accounts.world.trixel_ancestors(args.id)?;
 
     // Verify we have the correct number of remaining accounts
     require!(
@@ -71,24 +90,24 @@ pub fn handle_create_trixel_and_ancestors<'info>(ctx: Context<'_, '_, 'info, 'in
         let ancestor_id_bytes = ancestor_id.to_le_bytes();
         let trixel_resolution = resolution_from_trixel_id(ancestor_id)?;
 
-        // Derive the PDA for this ancestor
-        let (ancestor_pda, ancestor_bump) = Pubkey::find_program_address(
-            &[
-                b"trixel",
-                ctx.accounts.world.key().as_ref(),
-                ancestor_id_bytes.as_ref()
-            ],
-            ctx.program_id
-        );
-
-        // Verify this remaining account matches the expected PDA
-        require!(
-            rem_acc.key() == ancestor_pda,
-            ErrorCode::InvalidTrixelAccount
-        );
-
         // Check if the ancestor account exists
         if rem_acc.data_is_empty() {
+            // Derive the PDA for this ancestor, only new ones need the search
+            let (ancestor_pda, ancestor_bump) = Pubkey::find_program_address(
+                &[
+                    b"trixel",
+                    ctx.accounts.world.key().as_ref(),
+                    ancestor_id_bytes.as_ref()
+                ],
+                ctx.program_id
+            );
+
+            // Verify this remaining account matches the expected PDA
+            require!(
+                rem_acc.key() == ancestor_pda,
+                ErrorCode::InvalidTrixelAccount
+            );
+
             // Create the ancestor account
             let rent = Rent::get()?;
             let space = Trixel::bytes();
@@ -118,34 +137,43 @@ pub fn handle_create_trixel_and_ancestors<'info>(ctx: Context<'_, '_, 'info, 'in
                     &[ancestor_bump],
                 ]],
             )?;
-            let mut ancestor_account_data = Account::<'info, Trixel>::try_from_unchecked(rem_acc)?;
+            let ancestor_loader = AccountLoader::<'info, Trixel>::try_from_unchecked(ctx.program_id, rem_acc)?;
+            let mut ancestor_account_data = ancestor_loader.load_init()?;
             ancestor_account_data.init(
                 ctx.accounts.world.key(),
                 ancestor_id,
                 trixel_resolution,
+                ancestor_bump,
+                ctx.accounts.world.hash_version,
                 world_data_type
             )?;
             // Give it the child hash for the previous
             ancestor_account_data.update_child_hash(prev_child_idx, prev_hash)?;
             // Get the updated hash for this ancestor
-            (prev_child_idx, prev_resolution) = get_child_index(ancestor_account_data.id)?;
+            (prev_child_idx, _) = get_child_index(ancestor_account_data.id)?;
             prev_hash = ancestor_account_data.hash;
-            // Exit the account so it's saved
-            ancestor_account_data.exit(&*ctx.program_id)?;
+            // Exit the account so its discriminator is written
+            drop(ancestor_account_data);
+            ancestor_loader.exit(ctx.program_id)?;
         } else {
-            let mut ancestor = Account::<'info, Trixel>::try_from(rem_acc)?;
+            let ancestor_loader = AccountLoader::<'info, Trixel>::try_from(rem_acc)?;
+            let mut ancestor = ancestor_loader.load_mut()?;
+            verify_trixel_bump(rem_acc, ancestor_id, &ctx.accounts.world.key(), ancestor.bump)?;
+            require!(!ancestor.is_frozen(), ErrorCode::TrixelFrozen);
+            // A leaf that has been written holds its own data, so nothing can go beneath it
+            require!(ancestor.has_children() || ancestor.updates == 0, ErrorCode::ResolutionConflict);
             // Give it the child hash for the previous
             ancestor.update_child_hash(prev_child_idx, prev_hash)?;
             // Get the updated hash for this ancestor
-            (prev_child_idx, prev_resolution) = get_child_index(ancestor.id)?;
+            (prev_child_idx, _) = get_child_index(ancestor.id)?;
             prev_hash = ancestor.hash;
-            // Exit the account so it's saved
-            ancestor.exit(&*ctx.program_id)?;
         }
     }
 
     // Update the World
-    ctx.accounts.world.update_child_hash_and_root(prev_child_idx, prev_hash)?;
+    let root_idx = ctx.accounts.world.root_index(ancestors.last().copied().unwrap_or(args.id))?;
+    let world_key = ctx.accounts.world.key();
+    ctx.accounts.world.update_child_hash_and_root(&world_key, root_idx, prev_hash)?;
 
 
     Ok(())
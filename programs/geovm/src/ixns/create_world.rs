@@ -30,13 +30,15 @@ pub struct CreateWorldCtx<'info> {
 }
 
 pub fn handle_create_world(ctx: Context<CreateWorldCtx>, args: CreateWorldArgs) -> Result<()> {
+    let world_key = ctx.accounts.world.key();
     let world = &mut ctx.accounts.world;
     world.init(
+        &world_key,
         ctx.accounts.authority.key(),
         args.name,
         args.canonical_resolution,
         args.permissioned_updates,
         args.data_type
-    );
+    )?;
     Ok(())
 }
@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Stake, World};
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::{transfer_lamports, transfer_tokens};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct DepositStakeArgs {
+    pub amount: u64,  // In lamports or tokens of the world's stake mint
+}
+
+#[derive(Accounts)]
+pub struct DepositStakeCtx<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = Stake::bytes(),
+        seeds = [b"stake", world.key().as_ref(), staker.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, Stake>,
+
+    // Token stakes only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub staker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        associated_token::mint = mint,
+        associated_token::authority = stake,
+        associated_token::token_program = token_program
+    )]
+    pub stake_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_deposit_stake(ctx: Context<DepositStakeCtx>, args: DepositStakeArgs) -> Result<()> {
+    require!(args.amount > 0, ErrorCode::InvalidArgument);
+    let accounts = &mut *ctx.accounts;
+    let stake = &mut accounts.stake;
+
+    // An empty stake follows the world's current stake mint
+    if stake.amount == 0 && stake.unbonding_amount == 0 {
+        stake.world = accounts.world.key();
+        stake.authority = accounts.staker.key();
+        stake.mint = accounts.world.stake_mint;
+    }
+    require!(stake.mint == accounts.world.stake_mint, ErrorCode::AccountMismatch);
+
+    match stake.mint {
+        None => transfer_lamports(
+            accounts.staker.to_account_info(),
+            stake.to_account_info(),
+            accounts.system_program.to_account_info(),
+            args.amount
+        )?,
+        Some(stake_mint) => {
+            let (Some(mint), Some(staker_token_account), Some(stake_token_account), Some(token_program)) = (
+                accounts.mint.as_ref(),
+                accounts.staker_token_account.as_ref(),
+                accounts.stake_token_account.as_ref(),
+                accounts.token_program.as_ref()
+            ) else {
+                return Err(ErrorCode::MissingTokenAccount.into());
+            };
+            require!(mint.key() == stake_mint, ErrorCode::AccountMismatch);
+
+            transfer_tokens(
+                staker_token_account.to_account_info(),
+                stake_token_account.to_account_info(),
+                accounts.staker.to_account_info(),
+                mint,
+                token_program.to_account_info(),
+                args.amount,
+                &[]
+            )?;
+        }
+    }
+
+    stake.amount = stake.amount.checked_add(args.amount).ok_or(ErrorCode::ArithmeticOverflow)?;
+    Ok(())
+}
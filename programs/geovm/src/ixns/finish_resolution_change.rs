@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use crate::state::{Trixel, TrixelData, World};
+use crate::errors::ErrorCode;
+use crate::helpers::htm::get_trixel_pda;
+
+/// Remaining accounts hold the top trixels of the world's hash tree in order,
+/// the 8 faces or the bound roots when rooted at the bounds, including those
+/// that don't exist
+#[derive(Accounts)]
+pub struct FinishResolutionChangeCtx<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAction,
+        constraint = world.resolution_change.is_some() @ ErrorCode::InvalidArgument
+    )]
+    pub world: Account<'info, World>,
+}
+
+/// Completes a resolution change once every batch of retarget_trixels and
+/// rehash_trixels has run, recomputing the world's data and root hash from the
+/// top trixels and accepting writes at the new canonical resolution
+pub fn handle_finish_resolution_change<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FinishResolutionChangeCtx<'info>>
+) -> Result<()> {
+    let world = &mut ctx.accounts.world;
+    let world_key = world.key();
+    let change = world.resolution_change.ok_or(ErrorCode::InvalidArgument)?;
+    let tops: Vec<u64> = if world.rooted_at_bounds {
+        world.bound_roots().collect()
+    } else {
+        (1..=8).collect()
+    };
+    require!(
+        ctx.remaining_accounts.len() == tops.len(),
+        ErrorCode::InvalidArgument
+    );
+
+    let mut data = TrixelData::zero(world.data.to_data_type());
+    let mut child_hashes = [[0; 32]; 8];
+    for (i, (id, account)) in tops.iter().zip(ctx.remaining_accounts.iter()).enumerate() {
+        require!(
+            account.key() == get_trixel_pda(*id, &world_key).0,
+            ErrorCode::InvalidTrixelAccount
+        );
+        if account.data_is_empty() {
+            continue;
+        }
+        let trixel_loader = AccountLoader::<'info, Trixel>::try_from(account)?;
+        let trixel = trixel_loader.load()?;
+        data.add(&trixel.data()?)?;
+        child_hashes[i] = trixel.hash;
+    }
+
+    world.data = data;
+    world.child_hashes = child_hashes;
+    world.canonical_resolution = change.target_resolution;
+    world.resolution_change = None;
+    world.refresh_root_hash(&world_key)?;
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::Bounty;
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::{transfer_lamports, transfer_tokens};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct FundBountyArgs {
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct FundBountyCtx<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(mut)]
+    pub bounty: Account<'info, Bounty>,
+
+    // Token bounties only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub funder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bounty
+    )]
+    pub bounty_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_fund_bounty(ctx: Context<FundBountyCtx>, args: FundBountyArgs) -> Result<()> {
+    let accounts = &ctx.accounts;
+
+    let Some(bounty_mint) = accounts.bounty.mint else {
+        return transfer_lamports(
+            accounts.funder.to_account_info(),
+            accounts.bounty.to_account_info(),
+            accounts.system_program.to_account_info(),
+            args.amount
+        );
+    };
+
+    let (Some(mint), Some(funder_token_account), Some(bounty_token_account), Some(token_program)) = (
+        accounts.mint.as_ref(),
+        accounts.funder_token_account.as_ref(),
+        accounts.bounty_token_account.as_ref(),
+        accounts.token_program.as_ref()
+    ) else {
+        return Err(ErrorCode::MissingTokenAccount.into());
+    };
+    require!(mint.key() == bounty_mint, ErrorCode::AccountMismatch);
+
+    transfer_tokens(
+        funder_token_account.to_account_info(),
+        bounty_token_account.to_account_info(),
+        accounts.funder.to_account_info(),
+        mint,
+        token_program.to_account_info(),
+        args.amount,
+        &[]
+    )
+}
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use crate::state::{Trixel, TrixelData, World};
+use crate::errors::ErrorCode;
+use crate::helpers::htm::{get_child_index, get_trixel_pda, resolution_from_trixel_id, verify_trixel_bump, TrixelSet};
+
+/// The summed value of a region as returned by `get_region_value`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RegionValue {
+    pub world: Pubkey,
+    pub trixels: u32,  // Number of trixels summed, unwritten ones included
+    pub data: TrixelData,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct GetRegionValueArgs {
+    pub ids: Vec<u64>,  // The region as compacted by TrixelSet::ids, no deeper than the canonical resolution
+}
+
+/// Remaining accounts hold the trixel of each id, in the same order. Trixels without
+/// an account yet pass their PDA instead, followed by the account of their closest
+/// ancestor that exists (or the PDA of the top of their tree if none does), which
+/// shows no data is held beneath or above them
+#[derive(Accounts)]
+pub struct GetRegionValueCtx<'info> {
+    pub world: Account<'info, World>,
+}
+
+/// Returns the sum of the trixels covering a region through the transaction's
+/// return data. Whole subtrees are read from their coarsest trixel, so the ids
+/// must already be compacted
+pub fn handle_get_region_value<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetRegionValueCtx<'info>>,
+    args: GetRegionValueArgs
+) -> Result<RegionValue> {
+    require!(!args.ids.is_empty(), ErrorCode::InvalidArgument);
+
+    // Compacting also rules out overlapping trixels, which would be counted twice
+    require!(
+        TrixelSet::from_ids(&args.ids)?.ids() == args.ids,
+        ErrorCode::InvalidArgument
+    );
+
+    let world = &ctx.accounts.world;
+    let world_key = world.key();
+    let mut data: Option<TrixelData> = None;
+    let mut accounts = ctx.remaining_accounts.iter();
+    for id in args.ids.iter() {
+        require!(
+            resolution_from_trixel_id(*id)? <= world.canonical_resolution,
+            ErrorCode::InvalidResolution
+        );
+        let account = accounts.next().ok_or(ErrorCode::InvalidArgument)?;
+
+        let trixel_data = if account.data_is_empty() {
+            require!(
+                account.key() == get_trixel_pda(*id, &world_key).0,
+                ErrorCode::InvalidTrixelAccount
+            );
+            // Fails for trixels above the bound roots of a world rooted at its
+            // bounds, which are never created though their descendants hold data
+            let ancestors = world.trixel_ancestors(*id)?;
+            if !ancestors.is_empty() {
+                let ancestor_account = accounts.next().ok_or(ErrorCode::InvalidArgument)?;
+                check_empty_branch(world, *id, &ancestors, ancestor_account)?;
+            }
+            TrixelData::zero(world.data.to_data_type())
+        } else {
+            let trixel_loader = AccountLoader::<'info, Trixel>::try_from(account)?;
+            let trixel = trixel_loader.load()?;
+            verify_trixel_bump(account, *id, &world_key, trixel.bump)?;
+            require!(trixel.world == world_key, ErrorCode::AccountMismatch);
+            trixel.data()?
+        };
+
+        match data.as_mut() {
+            Some(data) => data.add(&trixel_data)?,
+            None => data = Some(trixel_data),
+        }
+    }
+    require!(accounts.next().is_none(), ErrorCode::InvalidArgument);
+
+    Ok(RegionValue {
+        world: world_key,
+        trixels: args.ids.len() as u32,
+        data: data.ok_or(ErrorCode::InvalidArgument)?,
+    })
+}
+
+/// Checks that trixel `id`, which has no account, holds no data, given the account
+/// of its closest existing ancestor
+///
+/// Either no ancestor exists, so the whole tree is empty, or the closest one has
+/// children but none on the way to `id`, or it is a leaf that has never been
+/// written. A written leaf covers `id` with its own data, which can't be split
+fn check_empty_branch<'info>(
+    world: &Account<'info, World>,
+    id: u64,
+    ancestors: &[u64],
+    account: &'info AccountInfo<'info>
+) -> Result<()> {
+    let world_key = world.key();
+    let position = ancestors.iter()
+        .position(|ancestor_id| account.key() == get_trixel_pda(*ancestor_id, &world_key).0)
+        .ok_or(ErrorCode::InvalidTrixelAccount)?;
+    if account.data_is_empty() {
+        // Trixels are created along with all their ancestors
+        require!(position == ancestors.len() - 1, ErrorCode::InvalidArgument);
+        return Ok(());
+    }
+
+    let ancestor_loader = AccountLoader::<'info, Trixel>::try_from(account)?;
+    let ancestor = ancestor_loader.load()?;
+    verify_trixel_bump(account, ancestors[position], &world_key, ancestor.bump)?;
+    require!(ancestor.world == world_key, ErrorCode::AccountMismatch);
+    if ancestor.has_children() {
+        let child_id = if position == 0 { id } else { ancestors[position - 1] };
+        let (child_idx, _) = get_child_index(child_id)?;
+        require!(ancestor.child_hashes[child_idx] == [0; 32], ErrorCode::InvalidArgument);
+    } else {
+        require!(
+            ancestor.updates == 0 && ancestor.data()? == TrixelData::zero(world.data.to_data_type()),
+            ErrorCode::ResolutionConflict
+        );
+    }
+    Ok(())
+}
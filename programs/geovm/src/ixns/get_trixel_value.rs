@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::{Trixel, TrixelData, World};
+
+/// A trixel's value as returned to other programs by `get_trixel_value`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct TrixelValue {
+    pub world: Pubkey,
+    pub id: u64,
+    pub resolution: u8,
+    pub data: TrixelData,
+    pub updates: u64,
+    pub last_update: i64,
+    pub hash: [u8;32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct GetTrixelValueArgs {
+    pub id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: GetTrixelValueArgs)]
+pub struct GetTrixelValueCtx<'info> {
+    pub world: Account<'info, World>,
+
+    #[account(
+        has_one = world,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump = trixel.load()?.bump
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+}
+
+/// Returns a trixel's value through the transaction's return data, see `reader`
+/// for a typed helper
+pub fn handle_get_trixel_value(ctx: Context<GetTrixelValueCtx>, _args: GetTrixelValueArgs) -> Result<TrixelValue> {
+    let trixel = ctx.accounts.trixel.load()?;
+    Ok(TrixelValue {
+        world: trixel.world,
+        id: trixel.id,
+        resolution: trixel.resolution,
+        data: trixel.data()?,
+        updates: trixel.updates,
+        last_update: trixel.last_update,
+        hash: trixel.hash,
+    })
+}
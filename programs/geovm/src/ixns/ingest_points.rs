@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use crate::state::{Stake, Trixel, World};
+use crate::errors::ErrorCode;
+use crate::helpers::reporter::PreviousWrite;
+use crate::helpers::writes::*;
+use crate::helpers::htm::{get_trixel_id, verify_trixel_bump, SphericalCoords};
+use super::update_trixel::{apply_trixel_write, TrixelWrite};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PointValue {
+    pub coords: SphericalCoords,
+    pub value: i32,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct IngestPointsArgs {
+    pub points: Vec<PointValue>,  // Binned into the world's canonical trixels on-chain
+}
+
+/// Remaining accounts hold, for each trixel the points bin into (in order of the
+/// first point in it), the trixel followed by its ancestors from closest to furthest
+///
+/// `write.update_record` is unused, as worlds with a challenge window don't take
+/// bulk writes
+#[derive(Accounts)]
+pub struct IngestPointsCtx<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = world.authority == payer.key() || !world.permissioned_updates @ ErrorCode::UnauthorizedAction,
+        constraint = world.authority == payer.key() || Stake::covers(write.stake.as_ref(), &world, payer.key()) @ ErrorCode::InsufficientStake
+    )]
+    pub world: Account<'info, World>,
+
+    pub system_program: Program<'info, System>,
+
+    pub write: WriteAccounts<'info>,
+}
+
+/// Bins points into the world's canonical trixels and writes each bin's folded
+/// value in one go
+///
+/// A bin is a single write: it is charged one fee, counts once against the
+/// payer's epoch limit and the trixel's cooldown, and earns at most one bounty,
+/// however many points fall in it
+pub fn handle_ingest_points<'info>(
+    ctx: Context<'_, '_, 'info, 'info, IngestPointsCtx<'info>>,
+    args: IngestPointsArgs
+) -> Result<()> {
+    // Worlds using commit-reveal only accept values through reveal_trixel_update
+    require!(
+        ctx.accounts.world.reveal_window == 0,
+        ErrorCode::CommitRevealRequired
+    );
+    // Disputed writes need an update record each, which bulk writes don't create
+    require!(
+        ctx.accounts.world.challenge_window == 0 && ctx.accounts.write.update_record.is_none(),
+        ErrorCode::InvalidArgument
+    );
+    require!(!args.points.is_empty(), ErrorCode::InvalidArgument);
+
+    // Bin the points into canonical trixels, folding each bin into one write
+    let resolution = ctx.accounts.world.canonical_resolution;
+    let data_type = ctx.accounts.world.data.to_data_type();
+    let mut writes: Vec<TrixelWrite> = Vec::new();
+    for point in args.points.iter() {
+        let id = get_trixel_id(point.coords, resolution)?;
+        match writes.iter_mut().find(|write| write.id == id) {
+            Some(write) => write.add_point(data_type, point.value)?,
+            None => writes.push(TrixelWrite { id, value: point.value.into(), points: 1 }),
+        }
+    }
+
+    let updater = ctx.accounts.write.updater(&ctx.accounts.payer);
+    let world_key = ctx.accounts.world.key();
+    let mut remaining = ctx.remaining_accounts;
+    for write in writes {
+        let chain_len = ctx.accounts.world.trixel_ancestors(write.id)?.len() + 1;
+        require!(remaining.len() >= chain_len, ErrorCode::InvalidArgument);
+        let (chain, rest) = remaining.split_at(chain_len);
+        remaining = rest;
+
+        let trixel_loader = AccountLoader::<'info, Trixel>::try_from(&chain[0])?;
+        let mut trixel = trixel_loader.load_mut()?;
+        verify_trixel_bump(&chain[0], write.id, &world_key, trixel.bump)?;
+
+        ctx.accounts.write.reporter.record_write(&ctx.accounts.world, &ctx.accounts.payer)?;
+        ctx.accounts.write.fee.collect(
+            &ctx.accounts.world,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            resolution
+        )?;
+
+        let previous = PreviousWrite {
+            updates: trixel.updates,
+            last_update: trixel.last_update,
+        };
+        apply_trixel_write(
+            &mut ctx.accounts.world,
+            &mut trixel,
+            &chain[1..],
+            &updater,
+            write
+        )?;
+        drop(trixel);
+
+        ctx.accounts.write.reporter.pay_bounty(
+            &ctx.accounts.world,
+            &ctx.accounts.payer,
+            write.id,
+            resolution,
+            previous
+        )?;
+    }
+    require!(remaining.is_empty(), ErrorCode::InvalidArgument);
+
+    Ok(())
+}
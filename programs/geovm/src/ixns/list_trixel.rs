@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Listing, Trixel, World};
+use crate::state::listing::ListingKind;
+use crate::errors::ErrorCode;
+use crate::helpers::ownership::TrixelUpdater;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ListTrixelArgs {
+    pub id: u64,
+    pub price: u64,  // In lamports or tokens of `mint`, the reserve price for auctions
+    pub auction_duration: Option<i64>,  // Seconds to accept bids for, None lists at a fixed price
+}
+
+#[derive(Accounts)]
+#[instruction(args: ListTrixelArgs)]
+pub struct ListTrixelCtx<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        constraint = TrixelUpdater::new(seller.key(), owner_token_account.as_ref()).owns(trixel.load()?.owner()) @ ErrorCode::NotTrixelOwner,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump = trixel.load()?.bump
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+
+    // Proves the seller holds the token of a minted trixel
+    pub owner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = Listing::bytes(),
+        seeds = [b"listing", trixel.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    // Token listings only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    // Token auctions only, escrows the bids
+    #[account(
+        init,
+        payer = seller,
+        associated_token::mint = mint,
+        associated_token::authority = listing,
+        associated_token::token_program = token_program
+    )]
+    pub listing_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_list_trixel(ctx: Context<ListTrixelCtx>, args: ListTrixelArgs) -> Result<()> {
+    let mint = ctx.accounts.mint.as_ref().map(|mint| mint.key());
+    let kind = match args.auction_duration {
+        None => {
+            require!(args.price > 0, ErrorCode::InvalidArgument);
+            // Fixed price sales pay the seller directly, so there is nothing to escrow
+            require!(ctx.accounts.listing_token_account.is_none(), ErrorCode::InvalidAccount);
+            ListingKind::FixedPrice
+        }
+        Some(duration) => {
+            require!(duration > 0, ErrorCode::InvalidArgument);
+            if mint.is_some() {
+                require!(ctx.accounts.listing_token_account.is_some(), ErrorCode::MissingTokenAccount);
+            }
+            let end_time = Clock::get()?.unix_timestamp
+                .checked_add(duration)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            ListingKind::Auction { end_time }
+        }
+    };
+
+    let listing = &mut ctx.accounts.listing;
+    listing.world = ctx.accounts.world.key();
+    listing.trixel = ctx.accounts.trixel.key();
+    listing.seller = ctx.accounts.seller.key();
+    listing.owner = ctx.accounts.trixel.load()?.owner;
+    listing.mint = mint;
+    listing.price = args.price;
+    listing.kind = kind;
+    listing.highest_bidder = None;
+    listing.highest_bid = 0;
+
+    // The listing holds the trixel until it is sold or the listing is cancelled
+    ctx.accounts.trixel.load_mut()?.set_owner(Some(listing.key()));
+    Ok(())
+}
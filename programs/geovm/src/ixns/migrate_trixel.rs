@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::state::{Trixel, World};
+use crate::state::trixel::LegacyTrixel;
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::withdraw_lamports;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MigrateTrixelArgs {
+    pub id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: MigrateTrixelArgs)]
+pub struct MigrateTrixelCtx<'info> {
+    // Receives the rent freed by the smaller layout
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority @ ErrorCode::UnauthorizedAction
+    )]
+    pub world: Account<'info, World>,
+
+    /// CHECK: A trixel still in the legacy layout, checked in the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub trixel: UncheckedAccount<'info>,
+}
+
+/// Rewrites a trixel created before trixels were zero-copy in the zero-copy
+/// layout, in place, along with its discriminator. The account shrinks so the
+/// freed rent goes to the authority
+pub fn handle_migrate_trixel(ctx: Context<MigrateTrixelCtx>, args: MigrateTrixelArgs) -> Result<()> {
+    let trixel_info = ctx.accounts.trixel.to_account_info();
+
+    let legacy = {
+        let data = trixel_info.try_borrow_data()?;
+        require!(
+            data.len() > Trixel::bytes() && data[..8] == LegacyTrixel::DISCRIMINATOR,
+            ErrorCode::InvalidAccount
+        );
+        LegacyTrixel::deserialize(&mut &data[8..])?
+    };
+    require!(
+        legacy.world == ctx.accounts.world.key() && legacy.id == args.id,
+        ErrorCode::AccountMismatch
+    );
+
+    trixel_info.realloc(Trixel::bytes(), false)?;
+    {
+        let mut data = trixel_info.try_borrow_mut_data()?;
+        data[..8].copy_from_slice(Trixel::DISCRIMINATOR);
+        data[8..].fill(0);
+        let trixel: &mut Trixel = bytemuck::from_bytes_mut(&mut data[8..]);
+        trixel.migrate(&legacy, ctx.bumps.trixel);
+    }
+
+    let freed = trixel_info.lamports().saturating_sub(Rent::get()?.minimum_balance(Trixel::bytes()));
+    withdraw_lamports(&trixel_info, &ctx.accounts.authority.to_account_info(), freed)
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::state::World;
+use crate::state::world::LegacyWorld;
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::transfer_lamports;
+
+#[derive(Accounts)]
+pub struct MigrateWorldCtx<'info> {
+    // Pays for the world to grow to the current layout
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: A world still in the legacy layout, checked in the handler
+    #[account(
+        mut,
+        owner = crate::ID
+    )]
+    pub world: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a world created before the fields added since to the current layout,
+/// in place. The discriminator is unchanged, only the size tells the layouts
+/// apart. Until it is migrated, no instruction taking the world can load it
+pub fn handle_migrate_world(ctx: Context<MigrateWorldCtx>) -> Result<()> {
+    let world_info = ctx.accounts.world.to_account_info();
+
+    let legacy = {
+        let data = world_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data.len() < World::bytes() && data[..8] == *World::DISCRIMINATOR,
+            ErrorCode::InvalidAccount
+        );
+        LegacyWorld::deserialize(&mut &data[8..])?
+    };
+    require!(legacy.authority == ctx.accounts.authority.key(), ErrorCode::UnauthorizedAction);
+
+    let space = World::bytes();
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(space);
+    let lamports = world_info.lamports();
+    if rent_exempt_minimum > lamports {
+        transfer_lamports(
+            ctx.accounts.authority.to_account_info(),
+            world_info.clone(),
+            ctx.accounts.system_program.to_account_info(),
+            rent_exempt_minimum - lamports
+        )?;
+    }
+    world_info.realloc(space, true)?;
+
+    let mut world = World::default();
+    world.migrate(&legacy);
+    let mut data = world_info.try_borrow_mut_data()?;
+    world.try_serialize(&mut &mut data[..])
+}
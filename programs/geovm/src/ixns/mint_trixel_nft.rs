@@ -0,0 +1,182 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use anchor_spl::token_interface::{
+    self, Mint, MintTo, SetAuthority, Token2022, TokenAccount,
+    TokenMetadataInitialize, TokenMetadataUpdateField,
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_metadata_interface::state::{Field, TokenMetadata},
+};
+use crate::state::{Trixel, World};
+use crate::errors::ErrorCode;
+use crate::helpers::htm::{cartesian_to_spherical, get_trixel_vertices, resolution_from_trixel_id};
+use crate::helpers::transfers::transfer_lamports;
+
+pub const TRIXEL_NFT_SYMBOL: &str = "TRIXEL";
+pub const MAX_TRIXEL_NFT_URI_LENGTH: usize = 200;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MintTrixelNftArgs {
+    pub id: u64,
+    pub uri: String,  // Off-chain metadata, may be empty
+}
+
+#[derive(Accounts)]
+#[instruction(args: MintTrixelNftArgs)]
+pub struct MintTrixelNftCtx<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        constraint = trixel.load()?.owner() == Some(owner.key()) @ ErrorCode::NotTrixelOwner,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump = trixel.load()?.bump
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"trixel_mint", trixel.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = mint,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = mint,
+        extensions::metadata_pointer::metadata_address = mint
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Mints a Token-2022 NFT for an owned trixel and hands the trixel's write
+/// rights to whoever holds it. The metadata lives on the mint itself and
+/// records the trixel's world, id, resolution and vertices
+pub fn handle_mint_trixel_nft(ctx: Context<MintTrixelNftCtx>, args: MintTrixelNftArgs) -> Result<()> {
+    require!(args.uri.len() <= MAX_TRIXEL_NFT_URI_LENGTH, ErrorCode::InvalidArgument);
+    let accounts = &ctx.accounts;
+    let mint_key = accounts.mint.key();
+
+    let vertices = get_trixel_vertices(args.id)?
+        .map(|vertex| {
+            let coords = cartesian_to_spherical(vertex);
+            format!("{:.6},{:.6}", coords.ra, coords.dec)
+        })
+        .join(";");
+    let additional_metadata = vec![
+        ("world".to_string(), accounts.world.key().to_string()),
+        ("trixel_id".to_string(), args.id.to_string()),
+        ("resolution".to_string(), resolution_from_trixel_id(args.id)?.to_string()),
+        ("vertices".to_string(), vertices),
+    ];
+    let metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey::try_from(Some(mint_key))?,
+        mint: mint_key,
+        name: format!("Trixel {}", args.id),
+        symbol: TRIXEL_NFT_SYMBOL.to_string(),
+        uri: args.uri.clone(),
+        additional_metadata: additional_metadata.clone(),
+    };
+
+    // Token-2022 reallocs the mint to fit the metadata but expects the rent up front
+    let metadata_size = metadata.tlv_size_of()?;
+    let mint_info = accounts.mint.to_account_info();
+    let rent_required = Rent::get()?.minimum_balance(mint_info.data_len() + metadata_size);
+    let top_up = rent_required.saturating_sub(mint_info.lamports());
+    if top_up > 0 {
+        transfer_lamports(
+            accounts.owner.to_account_info(),
+            mint_info.clone(),
+            accounts.system_program.to_account_info(),
+            top_up
+        )?;
+    }
+
+    let trixel_key = accounts.trixel.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"trixel_mint",
+        trixel_key.as_ref(),
+        &[ctx.bumps.mint],
+    ]];
+    let token_program = accounts.token_program.to_account_info();
+
+    token_interface::token_metadata_initialize(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TokenMetadataInitialize {
+                program_id: token_program.clone(),
+                metadata: mint_info.clone(),
+                update_authority: mint_info.clone(),
+                mint_authority: mint_info.clone(),
+                mint: mint_info.clone(),
+            },
+            signer_seeds
+        ),
+        metadata.name,
+        metadata.symbol,
+        metadata.uri
+    )?;
+    for (key, value) in additional_metadata {
+        token_interface::token_metadata_update_field(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataUpdateField {
+                    program_id: token_program.clone(),
+                    metadata: mint_info.clone(),
+                    update_authority: mint_info.clone(),
+                },
+                signer_seeds
+            ),
+            Field::Key(key),
+            value
+        )?;
+    }
+
+    // Mint the single token, then drop the mint authority so the supply stays at one
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            MintTo {
+                mint: mint_info.clone(),
+                to: accounts.owner_token_account.to_account_info(),
+                authority: mint_info.clone(),
+            },
+            signer_seeds
+        ),
+        1
+    )?;
+    token_interface::set_authority(
+        CpiContext::new_with_signer(
+            token_program,
+            SetAuthority {
+                current_authority: mint_info.clone(),
+                account_or_mint: mint_info,
+            },
+            signer_seeds
+        ),
+        AuthorityType::MintTokens,
+        None
+    )?;
+
+    // From now on the token, not the wallet, carries the trixel's write rights
+    ctx.accounts.trixel.load_mut()?.set_owner(Some(mint_key));
+    Ok(())
+}
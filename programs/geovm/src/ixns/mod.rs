@@ -5,4 +5,118 @@ pub mod create_trixel_and_ancestors;
 pub use create_trixel_and_ancestors::*;
 
 pub mod update_trixel;
-pub use update_trixel::*;
\ No newline at end of file
+pub use update_trixel::*;
+
+pub mod update_world;
+pub use update_world::*;
+
+pub mod commit_trixel_update;
+pub use commit_trixel_update::*;
+
+pub mod reveal_trixel_update;
+pub use reveal_trixel_update::*;
+
+pub mod set_fee_schedule;
+pub use set_fee_schedule::*;
+
+pub mod withdraw_treasury;
+pub use withdraw_treasury::*;
+
+pub mod register_reporter;
+pub use register_reporter::*;
+
+pub mod create_bounty;
+pub use create_bounty::*;
+
+pub mod fund_bounty;
+pub use fund_bounty::*;
+
+pub mod close_bounty;
+pub use close_bounty::*;
+
+pub mod set_trixel_frozen;
+pub use set_trixel_frozen::*;
+
+pub mod claim_trixel;
+pub use claim_trixel::*;
+
+pub mod transfer_trixel;
+pub use transfer_trixel::*;
+
+pub mod list_trixel;
+pub use list_trixel::*;
+
+pub mod buy_trixel;
+pub use buy_trixel::*;
+
+pub mod cancel_listing;
+pub use cancel_listing::*;
+
+pub mod place_bid;
+pub use place_bid::*;
+
+pub mod settle_auction;
+pub use settle_auction::*;
+
+pub mod mint_trixel_nft;
+pub use mint_trixel_nft::*;
+
+pub mod challenge_update;
+pub use challenge_update::*;
+
+pub mod resolve_challenge;
+pub use resolve_challenge::*;
+
+pub mod close_update_record;
+pub use close_update_record::*;
+
+pub mod deposit_stake;
+pub use deposit_stake::*;
+
+pub mod request_unstake;
+pub use request_unstake::*;
+
+pub mod withdraw_stake;
+pub use withdraw_stake::*;
+
+pub mod slash_stake;
+pub use slash_stake::*;
+
+pub mod get_trixel_value;
+pub use get_trixel_value::*;
+
+pub mod update_at_point;
+pub use update_at_point::*;
+
+pub mod ingest_points;
+pub use ingest_points::*;
+
+pub mod get_region_value;
+pub use get_region_value::*;
+
+pub mod migrate_trixel;
+pub use migrate_trixel::*;
+
+pub mod create_named_world;
+pub use create_named_world::*;
+
+pub mod set_world_metadata;
+pub use set_world_metadata::*;
+
+pub mod set_world_bounds;
+pub use set_world_bounds::*;
+
+pub mod start_resolution_change;
+pub use start_resolution_change::*;
+
+pub mod retarget_trixels;
+pub use retarget_trixels::*;
+
+pub mod rehash_trixels;
+pub use rehash_trixels::*;
+
+pub mod finish_resolution_change;
+pub use finish_resolution_change::*;
+
+pub mod migrate_world;
+pub use migrate_world::*;
\ No newline at end of file
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::Listing;
+use crate::state::listing::ListingKind;
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::{transfer_lamports, transfer_tokens, withdraw_lamports};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PlaceBidArgs {
+    pub amount: u64,  // Must meet the reserve price and beat the highest bid
+}
+
+#[derive(Accounts)]
+pub struct PlaceBidCtx<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", listing.trixel.as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// CHECK: refunded the bid being outbid, checked against the listing
+    #[account(mut)]
+    pub previous_bidder: Option<UncheckedAccount<'info>>,
+
+    // Token auctions only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = listing
+    )]
+    pub listing_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint
+    )]
+    pub previous_bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_place_bid(ctx: Context<PlaceBidCtx>, args: PlaceBidArgs) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let listing = &accounts.listing;
+    let ListingKind::Auction { end_time } = listing.kind else {
+        return Err(ErrorCode::InvalidListingKind.into());
+    };
+    require!(Clock::get()?.unix_timestamp < end_time, ErrorCode::AuctionEnded);
+    require!(
+        args.amount >= listing.price && args.amount > listing.highest_bid,
+        ErrorCode::BidTooLow
+    );
+
+    let Some(listing_mint) = listing.mint else {
+        // Escrow the new bid before refunding the one it replaces
+        transfer_lamports(
+            accounts.bidder.to_account_info(),
+            listing.to_account_info(),
+            accounts.system_program.to_account_info(),
+            args.amount
+        )?;
+        if let Some(highest_bidder) = listing.highest_bidder {
+            let previous_bidder = accounts.previous_bidder.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+            require!(previous_bidder.key() == highest_bidder, ErrorCode::AccountMismatch);
+            withdraw_lamports(
+                &listing.to_account_info(),
+                &previous_bidder.to_account_info(),
+                listing.highest_bid
+            )?;
+        }
+        return record_bid(ctx, args.amount);
+    };
+
+    let (Some(mint), Some(bidder_token_account), Some(listing_token_account), Some(token_program)) = (
+        accounts.mint.as_ref(),
+        accounts.bidder_token_account.as_ref(),
+        accounts.listing_token_account.as_ref(),
+        accounts.token_program.as_ref()
+    ) else {
+        return Err(ErrorCode::MissingTokenAccount.into());
+    };
+    require!(mint.key() == listing_mint, ErrorCode::AccountMismatch);
+
+    transfer_tokens(
+        bidder_token_account.to_account_info(),
+        listing_token_account.to_account_info(),
+        accounts.bidder.to_account_info(),
+        mint,
+        token_program.to_account_info(),
+        args.amount,
+        &[]
+    )?;
+    if let Some(highest_bidder) = listing.highest_bidder {
+        let previous_bidder_token_account = accounts.previous_bidder_token_account.as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?;
+        require!(previous_bidder_token_account.owner == highest_bidder, ErrorCode::AccountMismatch);
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            listing.trixel.as_ref(),
+            &[ctx.bumps.listing],
+        ]];
+        transfer_tokens(
+            listing_token_account.to_account_info(),
+            previous_bidder_token_account.to_account_info(),
+            listing.to_account_info(),
+            mint,
+            token_program.to_account_info(),
+            listing.highest_bid,
+            signer_seeds
+        )?;
+    }
+    record_bid(ctx, args.amount)
+}
+
+fn record_bid(ctx: Context<PlaceBidCtx>, amount: u64) -> Result<()> {
+    let listing = &mut ctx.accounts.listing;
+    listing.highest_bidder = Some(ctx.accounts.bidder.key());
+    listing.highest_bid = amount;
+    Ok(())
+}
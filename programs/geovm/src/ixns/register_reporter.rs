@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::{Reporter, World};
+
+#[derive(Accounts)]
+pub struct RegisterReporterCtx<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Reporter::bytes(),
+        seeds = [b"reporter", world.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub reporter: Account<'info, Reporter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_register_reporter(ctx: Context<RegisterReporterCtx>) -> Result<()> {
+    let reporter = &mut ctx.accounts.reporter;
+    reporter.world = ctx.accounts.world.key();
+    reporter.authority = ctx.accounts.payer.key();
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use crate::state::{Trixel, TrixelData, World};
+use crate::errors::ErrorCode;
+use crate::helpers::htm::{get_trixel_pda, resolution_from_trixel_id, verify_trixel_bump};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RehashTrixelsArgs {
+    pub ids: Vec<u64>,  // Trixels above the target resolution, finest first
+}
+
+/// Remaining accounts hold, for each id, the trixel followed by its 4 children,
+/// including those that don't exist
+#[derive(Accounts)]
+pub struct RehashTrixelsCtx<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority @ ErrorCode::UnauthorizedAction,
+        constraint = world.resolution_change.is_some() @ ErrorCode::InvalidArgument
+    )]
+    pub world: Account<'info, World>,
+}
+
+/// Rebuilds the data and hashes of a batch of trixels from their children, after
+/// retarget_trixels has moved the leaves beneath them. Batches must go from the
+/// finest resolution to the coarsest so each trixel sees its rebuilt children
+pub fn handle_rehash_trixels<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RehashTrixelsCtx<'info>>,
+    args: RehashTrixelsArgs
+) -> Result<()> {
+    let world = &ctx.accounts.world;
+    let world_key = world.key();
+    let change = world.resolution_change.ok_or(ErrorCode::InvalidArgument)?;
+    let world_data_type = world.data.to_data_type();
+    require!(
+        ctx.remaining_accounts.len() == args.ids.len() * 5,
+        ErrorCode::InvalidArgument
+    );
+
+    for (id, accounts) in args.ids.iter().zip(ctx.remaining_accounts.chunks(5)) {
+        let resolution = resolution_from_trixel_id(*id)?;
+        require!(resolution < change.target_resolution, ErrorCode::InvalidResolution);
+        let trixel_loader = AccountLoader::<'info, Trixel>::try_from(&accounts[0])?;
+        let mut trixel = trixel_loader.load_mut()?;
+        verify_trixel_bump(&accounts[0], *id, &world_key, trixel.bump)?;
+        require!(trixel.world == world_key, ErrorCode::AccountMismatch);
+        // Leaves of variable-resolution worlds hold their own data
+        if !trixel.has_children() {
+            continue;
+        }
+
+        let digit_place = 10u64.pow(u32::from(resolution) + 1);
+        let mut total = TrixelData::zero(world_data_type);
+        for (i, child_account) in accounts[1..].iter().enumerate() {
+            let child_id = (i as u64 + 1) * digit_place + id;
+            if child_account.data_is_empty() {
+                require!(
+                    child_account.key() == get_trixel_pda(child_id, &world_key).0,
+                    ErrorCode::InvalidTrixelAccount
+                );
+                trixel.child_hashes[i] = [0; 32];
+                continue;
+            }
+            let child_loader = AccountLoader::<'info, Trixel>::try_from(child_account)?;
+            let child = child_loader.load()?;
+            verify_trixel_bump(child_account, child_id, &world_key, child.bump)?;
+            total.add(&child.data()?)?;
+            trixel.child_hashes[i] = child.hash;
+        }
+        trixel.set_data(total);
+        trixel.refresh_hash()?;
+    }
+
+    Ok(())
+}
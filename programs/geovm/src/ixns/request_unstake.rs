@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::{Stake, World};
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RequestUnstakeArgs {
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstakeCtx<'info> {
+    pub staker: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        constraint = stake.authority == staker.key() @ ErrorCode::UnauthorizedAction,
+        seeds = [b"stake", world.key().as_ref(), staker.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, Stake>,
+}
+
+/// Starts unbonding part of a stake. Unbonding more before an earlier request has
+/// been withdrawn restarts the unbonding period for the whole unbonding amount
+pub fn handle_request_unstake(ctx: Context<RequestUnstakeCtx>, args: RequestUnstakeArgs) -> Result<()> {
+    require!(args.amount > 0, ErrorCode::InvalidArgument);
+    let stake = &mut ctx.accounts.stake;
+    stake.amount = stake.amount.checked_sub(args.amount).ok_or(ErrorCode::InsufficientFunds)?;
+    stake.unbonding_amount = stake.unbonding_amount.checked_add(args.amount).ok_or(ErrorCode::ArithmeticOverflow)?;
+    stake.unbonding_since = Clock::get()?.unix_timestamp;
+    Ok(())
+}
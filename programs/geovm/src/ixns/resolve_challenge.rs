@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use crate::state::{Challenge, Trixel, UpdateRecord, World};
+use crate::state::trixel_data::TrixelDataType;
+use crate::state::update_record::DisputeStatus;
+use crate::errors::ErrorCode;
+use crate::helpers::htm::{get_child_index, verify_trixel_bump};
+use crate::helpers::transfers::withdraw_lamports;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ResolveChallengeArgs {
+    pub upheld: bool,  // Whether the disputed write is reverted
+}
+
+#[derive(Accounts)]
+pub struct ResolveChallengeCtx<'info> {
+    #[account(
+        constraint = resolver.key() == world.authority || Some(resolver.key()) == world.arbiter @ ErrorCode::UnauthorizedAction
+    )]
+    pub resolver: Signer<'info>,
+
+    #[account(mut)]
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        address = update_record.trixel
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+
+    #[account(
+        mut,
+        has_one = world,
+        has_one = updater @ ErrorCode::AccountMismatch,
+        seeds = [b"update", trixel.key().as_ref(), update_record.update_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub update_record: Account<'info, UpdateRecord>,
+
+    #[account(
+        mut,
+        close = challenger,
+        has_one = update_record,
+        has_one = challenger @ ErrorCode::AccountMismatch,
+        seeds = [b"challenge", update_record.key().as_ref()],
+        bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    /// CHECK: receives the updater's bond if the challenge is upheld, checked against the challenge
+    #[account(mut)]
+    pub challenger: UncheckedAccount<'info>,
+
+    /// CHECK: receives the challenger's bond if the challenge is rejected, checked against the record
+    #[account(mut)]
+    pub updater: UncheckedAccount<'info>,
+}
+
+/// Settles a challenge. Upholding it reverts the write, passing the trixel's
+/// ancestors as remaining accounts (closest first), and slashes the updater's
+/// bond to the challenger. Rejecting it pays the challenger's bond to the updater
+///
+/// A write that can no longer be reverted, because it has been overwritten or its
+/// trixel has since been refined, refunds both bonds instead. Challenges cannot be
+/// upheld while the world's resolution is changing
+pub fn handle_resolve_challenge<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ResolveChallengeCtx<'info>>,
+    args: ResolveChallengeArgs
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    require!(
+        accounts.update_record.status == DisputeStatus::Challenged,
+        ErrorCode::InvalidDisputeStatus
+    );
+
+    if !args.upheld {
+        withdraw_lamports(
+            &accounts.challenge.to_account_info(),
+            &accounts.updater.to_account_info(),
+            accounts.challenge.bond
+        )?;
+        accounts.update_record.status = DisputeStatus::Settled;
+        return Ok(());
+    }
+
+    require!(accounts.world.resolution_change.is_none(), ErrorCode::ResolutionChangeInProgress);
+    let reverted = revert_trixel_update(
+        &mut accounts.world,
+        &mut *accounts.trixel.load_mut()?,
+        &accounts.update_record,
+        ctx.remaining_accounts
+    )?;
+    // The challenge closes to the challenger and the record to the updater, so
+    // leaving the bond in place refunds both
+    if reverted {
+        withdraw_lamports(
+            &accounts.update_record.to_account_info(),
+            &accounts.challenger.to_account_info(),
+            accounts.update_record.bond
+        )?;
+    }
+    accounts.update_record.close(accounts.updater.to_account_info())
+}
+
+/// Reverts a recorded write to `trixel` and rolls the reversal up through its
+/// ancestors (passed in order from closest to furthest) and the world
+///
+/// Accumulating data types subtract the write. Overwriting data types restore the
+/// previous reading if the write is still the latest. Returns false, leaving the
+/// trixel alone, when an overwrite has already been superseded or the trixel has
+/// been refined and its data moved to its children
+fn revert_trixel_update<'info>(
+    world: &mut Account<'info, World>,
+    trixel: &mut Trixel,
+    record: &UpdateRecord,
+    ancestor_accounts: &'info [AccountInfo<'info>]
+) -> Result<bool> {
+    if trixel.has_children() {
+        return Ok(false);
+    }
+    let world_key = world.key();
+    let is_latest = trixel.updates == record.update_index.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    let roll_up = match world.data.to_data_type() {
+        TrixelDataType::Count | TrixelDataType::AggregateAccumulate | TrixelDataType::MeanAccumulate => {
+            let mut roll_up = record.roll_up.negate();
+            // Later writes keep the trixel counted in its ancestors' means
+            if !is_latest {
+                roll_up.denominator = 0;
+            }
+            trixel.roll_up(&roll_up)?;
+            roll_up
+        }
+        TrixelDataType::AggregateOverwrite | TrixelDataType::MeanOverwrite => {
+            if !is_latest {
+                return Ok(false);
+            }
+            trixel.set_data(record.previous_data);
+            record.roll_up.negate()
+        }
+    };
+
+    trixel.last_update = Clock::get()?.unix_timestamp;
+    let mut prev_hash = trixel.refresh_hash()?;
+    let (mut prev_child_idx, _) = get_child_index(trixel.id)?;
+
+    let ancestors = world.trixel_ancestors(trixel.id)?;
+    require!(
+        ancestors.len() == ancestor_accounts.len(),
+        ErrorCode::InvalidArgument
+    );
+    for (ancestor_id, rem_acc) in ancestors.iter().zip(ancestor_accounts.iter()) {
+        let ancestor_loader = AccountLoader::<'info, Trixel>::try_from(rem_acc)?;
+        let mut ancestor = ancestor_loader.load_mut()?;
+        verify_trixel_bump(rem_acc, *ancestor_id, &world_key, ancestor.bump)?;
+        require!(ancestor.world == world_key, ErrorCode::AccountMismatch);
+
+        ancestor.roll_up(&roll_up)?;
+        prev_hash = ancestor.update_child_hash(prev_child_idx, prev_hash)?;
+        (prev_child_idx, _) = get_child_index(ancestor.id)?;
+    }
+
+    world.data.roll_up(&roll_up)?;
+    let root_idx = world.root_index(ancestors.last().copied().unwrap_or(trixel.id))?;
+    world.update_child_hash_and_root(&world_key, root_idx, prev_hash)?;
+    Ok(true)
+}
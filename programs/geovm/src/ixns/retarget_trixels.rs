@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use crate::state::{Trixel, TrixelData, World};
+use crate::errors::ErrorCode;
+use crate::helpers::htm::{get_trixel_pda, resolution_from_trixel_id, verify_trixel_bump};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RetargetTrixelsArgs {
+    pub ids: Vec<u64>,  // Leaves at the canonical resolution when refining, trixels at the target resolution when coarsening
+}
+
+/// Remaining accounts hold, for each id, the trixel followed by its 4 children
+#[derive(Accounts)]
+pub struct RetargetTrixelsCtx<'info> {
+    // Pays for the children created when refining, and gets the rent of those
+    // closed when coarsening
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority @ ErrorCode::UnauthorizedAction,
+        constraint = world.resolution_change.is_some() @ ErrorCode::InvalidArgument
+    )]
+    pub world: Account<'info, World>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves a batch of leaves to the target resolution of the world's resolution change
+///
+/// Refining splits each leaf that holds data or has been written into new children
+/// by the change's split rule. Coarsening merges each trixel's children into it and
+/// closes them, so no stale trixels are left beneath the new leaves. Children that
+/// are owned (which includes listed and minted ones) or frozen can't be closed, so
+/// their owner has to release them before the world can coarsen. Trixels already moved are
+/// skipped, so batches can be retried. The trixels above the batch are rebuilt
+/// afterwards by rehash_trixels
+pub fn handle_retarget_trixels<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RetargetTrixelsCtx<'info>>,
+    args: RetargetTrixelsArgs
+) -> Result<()> {
+    let world = &ctx.accounts.world;
+    let world_key = world.key();
+    let change = world.resolution_change.ok_or(ErrorCode::InvalidArgument)?;
+    let refining = change.target_resolution > world.canonical_resolution;
+    let world_data_type = world.data.to_data_type();
+    require!(
+        ctx.remaining_accounts.len() == args.ids.len() * 5,
+        ErrorCode::InvalidArgument
+    );
+
+    for (id, accounts) in args.ids.iter().zip(ctx.remaining_accounts.chunks(5)) {
+        let resolution = resolution_from_trixel_id(*id)?;
+        require!(
+            resolution == if refining { world.canonical_resolution } else { change.target_resolution },
+            ErrorCode::InvalidResolution
+        );
+        let trixel_loader = AccountLoader::<'info, Trixel>::try_from(&accounts[0])?;
+        let mut trixel = trixel_loader.load_mut()?;
+        verify_trixel_bump(&accounts[0], *id, &world_key, trixel.bump)?;
+        require!(trixel.world == world_key, ErrorCode::AccountMismatch);
+
+        let digit_place = 10u64.pow(u32::from(resolution) + 1);
+        if refining {
+            // A written leaf is split even if its data is back to zero, as it
+            // would otherwise block trixels from being created beneath it
+            let data = trixel.data()?;
+            if trixel.has_children() || (trixel.updates == 0 && data == TrixelData::zero(world_data_type)) {
+                continue;
+            }
+
+            let mut total = TrixelData::zero(world_data_type);
+            for (i, child_account) in accounts[1..].iter().enumerate() {
+                let child_id = (i as u64 + 1) * digit_place + id;
+                let bump = create_trixel_account(&ctx, child_account, &world_key, child_id)?;
+                let child_loader = AccountLoader::<'info, Trixel>::try_from_unchecked(ctx.program_id, child_account)?;
+                let mut child = child_loader.load_init()?;
+                child.init(world_key, child_id, change.target_resolution, bump, world.hash_version, world_data_type)?;
+                child.set_data(data.split(change.split_rule, i as u64, 4));
+                total.add(&child.data()?)?;
+                trixel.child_hashes[i] = child.refresh_hash()?;
+                drop(child);
+                child_loader.exit(ctx.program_id)?;
+            }
+            trixel.set_data(total);
+        } else {
+            if !trixel.has_children() {
+                continue;
+            }
+
+            for (i, child_account) in accounts[1..].iter().enumerate() {
+                let child_id = (i as u64 + 1) * digit_place + id;
+                if child_account.data_is_empty() {
+                    // Only the child's own address shows it was never created
+                    require!(
+                        child_account.key() == get_trixel_pda(child_id, &world_key).0,
+                        ErrorCode::InvalidTrixelAccount
+                    );
+                    continue;
+                }
+                let child_loader = AccountLoader::<'info, Trixel>::try_from(child_account)?;
+                {
+                    let child = child_loader.load()?;
+                    verify_trixel_bump(child_account, child_id, &world_key, child.bump)?;
+                    require!(!child.has_children(), ErrorCode::ResolutionConflict);
+                    // Closing the child would drop its owner, listing or mint with it
+                    require!(child.owner().is_none(), ErrorCode::TrixelAlreadyOwned);
+                    require!(!child.is_frozen(), ErrorCode::TrixelFrozen);
+                }
+                child_loader.close(ctx.accounts.authority.to_account_info())?;
+            }
+            let merged = trixel.data()?.merged();
+            trixel.set_data(merged);
+            trixel.child_hashes = [[0; 32]; 4];
+        }
+        trixel.refresh_hash()?;
+    }
+
+    Ok(())
+}
+
+/// Creates the account of a new trixel at its PDA, returning its bump
+fn create_trixel_account<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, RetargetTrixelsCtx<'info>>,
+    account: &AccountInfo<'info>,
+    world_key: &Pubkey,
+    id: u64
+) -> Result<u8> {
+    let id_bytes = id.to_le_bytes();
+    let (pda, bump) = Pubkey::find_program_address(
+        &[b"trixel", world_key.as_ref(), id_bytes.as_ref()],
+        ctx.program_id
+    );
+    require!(account.key() == pda, ErrorCode::InvalidTrixelAccount);
+    require!(account.data_is_empty(), ErrorCode::InvalidAccount);
+
+    let space = Trixel::bytes();
+    let create_account_ix = anchor_lang::solana_program::system_instruction::create_account(
+        &ctx.accounts.authority.key(),
+        &pda,
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        ctx.program_id,
+    );
+    anchor_lang::solana_program::program::invoke_signed(
+        &create_account_ix,
+        &[
+            ctx.accounts.authority.to_account_info(),
+            account.clone(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        &[&[b"trixel", world_key.as_ref(), id_bytes.as_ref(), &[bump]]],
+    )?;
+    Ok(bump)
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use crate::state::{Stake, Trixel, TrixelCommitment, World};
+use crate::errors::ErrorCode;
+use crate::helpers::reporter::PreviousWrite;
+use crate::helpers::writes::*;
+use crate::helpers::htm::resolution_from_trixel_id;
+use super::update_trixel::apply_trixel_update;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RevealTrixelUpdateArgs {
+    pub id: u64,
+    pub value: i32,
+    pub salt: [u8;32],
+}
+
+#[derive(Accounts)]
+#[instruction(args: RevealTrixelUpdateArgs)]
+pub struct RevealTrixelUpdateCtx<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = world.authority == payer.key() || !world.permissioned_updates @ ErrorCode::UnauthorizedAction,
+        constraint = world.authority == payer.key() || Stake::covers(write.stake.as_ref(), &world, payer.key()) @ ErrorCode::InsufficientStake
+    )]
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump = trixel.load()?.bump
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+
+    #[account(
+        mut,
+        close = payer,
+        has_one = world,
+        constraint = commitment.updater == payer.key() @ ErrorCode::UnauthorizedAction,
+        seeds = [b"commitment", world.key().as_ref(), payer.key().as_ref(), commitment.hash.as_ref()],
+        bump
+    )]
+    pub commitment: Account<'info, TrixelCommitment>,
+
+    pub system_program: Program<'info, System>,
+
+    pub write: WriteAccounts<'info>,
+}
+
+pub fn handle_reveal_trixel_update<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RevealTrixelUpdateCtx<'info>>,
+    args: RevealTrixelUpdateArgs
+) -> Result<()> {
+    // The revealed update must be exactly the one committed to
+    require!(
+        TrixelCommitment::compute_hash(
+            &ctx.accounts.world.key(),
+            &ctx.accounts.payer.key(),
+            args.id,
+            args.value,
+            &args.salt
+        ) == ctx.accounts.commitment.hash,
+        ErrorCode::CommitmentMismatch
+    );
+
+    // It must land after the commitment, so both can't be sent in one transaction
+    let clock = Clock::get()?;
+    require!(clock.slot > ctx.accounts.commitment.committed_slot, ErrorCode::CommitmentTooRecent);
+
+    // And it must be revealed within the world's reveal window
+    let now = clock.unix_timestamp;
+    let reveal_deadline = ctx.accounts.commitment.committed_at
+        .checked_add(ctx.accounts.world.reveal_window)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(now <= reveal_deadline, ErrorCode::CommitmentExpired);
+
+    ctx.accounts.write.reporter.record_write(&ctx.accounts.world, &ctx.accounts.payer)?;
+
+    let trixel_resolution = resolution_from_trixel_id(args.id)?;
+    ctx.accounts.write.fee.collect(
+        &ctx.accounts.world,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        trixel_resolution
+    )?;
+
+    let accounts = &mut *ctx.accounts;
+    let mut trixel = accounts.trixel.load_mut()?;
+    let previous_data = trixel.data()?;
+    let previous = PreviousWrite {
+        updates: trixel.updates,
+        last_update: trixel.last_update,
+    };
+    let roll_up = apply_trixel_update(
+        &mut accounts.world,
+        &mut trixel,
+        ctx.remaining_accounts,
+        &accounts.write.updater(&accounts.payer),
+        args.id,
+        args.value
+    )?;
+    drop(trixel);
+
+    accounts.write.finish_write(
+        &accounts.world,
+        &accounts.payer,
+        &accounts.trixel,
+        &accounts.system_program,
+        AppliedWrite {
+            id: args.id,
+            resolution: trixel_resolution,
+            value: args.value,
+            previous,
+            previous_data,
+            roll_up,
+        }
+    )
+}
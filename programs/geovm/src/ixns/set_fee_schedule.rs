@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::{FeeSchedule, Treasury, World};
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SetFeeScheduleArgs {
+    pub fee_schedule: Option<FeeSchedule>,  // None makes writes free again
+}
+
+#[derive(Accounts)]
+pub struct SetFeeScheduleCtx<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAction
+    )]
+    pub world: Account<'info, World>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Treasury::bytes(),
+        seeds = [b"treasury", world.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_fee_schedule(ctx: Context<SetFeeScheduleCtx>, args: SetFeeScheduleArgs) -> Result<()> {
+    ctx.accounts.treasury.world = ctx.accounts.world.key();
+    ctx.accounts.world.fee_schedule = args.fee_schedule;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::{Trixel, World};
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SetTrixelFrozenArgs {
+    pub id: u64,
+    pub frozen: bool,  // Freezing a trixel freezes its whole subtree
+}
+
+#[derive(Accounts)]
+#[instruction(args: SetTrixelFrozenArgs)]
+pub struct SetTrixelFrozenCtx<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority @ ErrorCode::UnauthorizedAction
+    )]
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump = trixel.load()?.bump
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+}
+
+pub fn handle_set_trixel_frozen(ctx: Context<SetTrixelFrozenCtx>, args: SetTrixelFrozenArgs) -> Result<()> {
+    ctx.accounts.trixel.load_mut()?.set_frozen(args.frozen);
+    Ok(())
+}
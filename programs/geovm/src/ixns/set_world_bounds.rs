@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::state::World;
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SetWorldBoundsArgs {
+    pub roots: Vec<u64>,  // Non-overlapping root trixels of the world's region, empty for the whole sphere
+    pub rooted: bool,  // Roots the hash tree at the region, only while the world has no trixels
+}
+
+#[derive(Accounts)]
+pub struct SetWorldBoundsCtx<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAction
+    )]
+    pub world: Account<'info, World>,
+}
+
+/// Restricts the trixels that can be created and written in a world to a region
+pub fn handle_set_world_bounds(ctx: Context<SetWorldBoundsCtx>, args: SetWorldBoundsArgs) -> Result<()> {
+    let world_key = ctx.accounts.world.key();
+    ctx.accounts.world.set_bounds(&args.roots, args.rooted)?;
+    // The root hash commits to the bounds
+    ctx.accounts.world.refresh_root_hash(&world_key)?;
+    Ok(())
+}
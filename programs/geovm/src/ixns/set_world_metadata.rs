@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use crate::state::{World, WorldMetadata};
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::{transfer_lamports, withdraw_lamports};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SetWorldMetadataArgs {
+    pub units: String,  // Unit of the world's values, e.g. "kWh"
+    pub decimals: u8,  // Values are divided by 10^decimals for display
+    pub description: String,
+    pub license: String,
+    pub uri: String,  // Metadata URI pointing to a JSON schema
+}
+
+#[derive(Accounts)]
+#[instruction(args: SetWorldMetadataArgs)]
+pub struct SetWorldMetadataCtx<'info> {
+    // Pays for the metadata to grow, and is refunded when it shrinks
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority @ ErrorCode::UnauthorizedAction
+    )]
+    pub world: Account<'info, World>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = WorldMetadata::bytes(&args.units, &args.description, &args.license, &args.uri),
+        seeds = [b"world_metadata", world.key().as_ref()],
+        bump
+    )]
+    pub world_metadata: Account<'info, WorldMetadata>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Replaces a world's metadata, resizing the account to fit it
+pub fn handle_set_world_metadata(ctx: Context<SetWorldMetadataCtx>, args: SetWorldMetadataArgs) -> Result<()> {
+    WorldMetadata::validate(&args.units, &args.description, &args.license, &args.uri)?;
+
+    let space = WorldMetadata::bytes(&args.units, &args.description, &args.license, &args.uri);
+    let metadata_info = ctx.accounts.world_metadata.to_account_info();
+    if metadata_info.data_len() != space {
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(space);
+        let lamports = metadata_info.lamports();
+        if rent_exempt_minimum > lamports {
+            transfer_lamports(
+                ctx.accounts.authority.to_account_info(),
+                metadata_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+                rent_exempt_minimum - lamports
+            )?;
+        }
+        metadata_info.realloc(space, false)?;
+        let excess = metadata_info.lamports().saturating_sub(rent_exempt_minimum);
+        if excess > 0 {
+            withdraw_lamports(&metadata_info, &ctx.accounts.authority.to_account_info(), excess)?;
+        }
+    }
+
+    let metadata = &mut ctx.accounts.world_metadata;
+    metadata.world = ctx.accounts.world.key();
+    metadata.units = args.units;
+    metadata.decimals = args.decimals;
+    metadata.description = args.description;
+    metadata.license = args.license;
+    metadata.uri = args.uri;
+    Ok(())
+}
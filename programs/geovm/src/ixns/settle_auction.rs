@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Listing, Trixel, World};
+use crate::state::listing::ListingKind;
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::{close_token_escrow, withdraw_lamports};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SettleAuctionArgs {
+    pub id: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: SettleAuctionArgs)]
+pub struct SettleAuctionCtx<'info> {
+    // Anyone may settle an auction once it has ended
+    pub payer: Signer<'info>,
+
+    /// CHECK: receives the winning bid and the listing's rent, checked against the listing
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump = trixel.load()?.bump
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+
+    #[account(
+        mut,
+        close = seller,
+        has_one = trixel,
+        has_one = seller @ ErrorCode::AccountMismatch,
+        seeds = [b"listing", trixel.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    // Token auctions only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = listing
+    )]
+    pub listing_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn handle_settle_auction(ctx: Context<SettleAuctionCtx>, _args: SettleAuctionArgs) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let listing = &accounts.listing;
+    let ListingKind::Auction { end_time } = listing.kind else {
+        return Err(ErrorCode::InvalidListingKind.into());
+    };
+    require!(Clock::get()?.unix_timestamp >= end_time, ErrorCode::AuctionNotEnded);
+
+    match listing.mint {
+        None => {
+            if listing.highest_bidder.is_some() {
+                withdraw_lamports(
+                    &listing.to_account_info(),
+                    &accounts.seller.to_account_info(),
+                    listing.highest_bid
+                )?;
+            }
+        }
+        Some(listing_mint) => {
+            let (Some(mint), Some(listing_token_account), Some(seller_token_account), Some(token_program)) = (
+                accounts.mint.as_ref(),
+                accounts.listing_token_account.as_ref(),
+                accounts.seller_token_account.as_ref(),
+                accounts.token_program.as_ref()
+            ) else {
+                return Err(ErrorCode::MissingTokenAccount.into());
+            };
+            require!(mint.key() == listing_mint, ErrorCode::AccountMismatch);
+
+            let trixel_key = accounts.trixel.key();
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"listing",
+                trixel_key.as_ref(),
+                &[ctx.bumps.listing],
+            ]];
+            // The escrow holds the winning bid, if any, and whatever else was sent to it
+            close_token_escrow(
+                listing_token_account,
+                Some(seller_token_account.to_account_info()),
+                Some(mint),
+                accounts.seller.to_account_info(),
+                listing.to_account_info(),
+                token_program.to_account_info(),
+                signer_seeds
+            )?;
+        }
+    }
+
+    // Unsold trixels go back to their owner when listed
+    let new_owner = ctx.accounts.listing.highest_bidder.unwrap_or(ctx.accounts.listing.owner);
+    ctx.accounts.trixel.load_mut()?.set_owner(Some(new_owner));
+    Ok(())
+}
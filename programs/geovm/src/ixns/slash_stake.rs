@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Stake, Treasury, World};
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::{transfer_tokens, withdraw_lamports};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SlashStakeArgs {
+    pub amount: u64,  // Capped at the reporter's total stake, active stake is slashed first
+}
+
+#[derive(Accounts)]
+pub struct SlashStakeCtx<'info> {
+    #[account(
+        mut,
+        constraint = slasher.key() == world.authority || Some(slasher.key()) == world.arbiter @ ErrorCode::UnauthorizedAction
+    )]
+    pub slasher: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        seeds = [b"stake", world.key().as_ref(), stake.authority.as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, Stake>,
+
+    // Slashed stake goes to the world treasury
+    #[account(
+        init_if_needed,
+        payer = slasher,
+        space = Treasury::bytes(),
+        seeds = [b"treasury", world.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    // Token stakes only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = stake
+    )]
+    pub stake_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = treasury
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_slash_stake(ctx: Context<SlashStakeCtx>, args: SlashStakeArgs) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    accounts.treasury.world = accounts.world.key();
+
+    let stake = &mut accounts.stake;
+    let from_active = args.amount.min(stake.amount);
+    let from_unbonding = (args.amount - from_active).min(stake.unbonding_amount);
+    let slashed = from_active + from_unbonding;
+    require!(slashed > 0, ErrorCode::InsufficientFunds);
+    stake.amount -= from_active;
+    stake.unbonding_amount -= from_unbonding;
+
+    match stake.mint {
+        None => withdraw_lamports(
+            &stake.to_account_info(),
+            &accounts.treasury.to_account_info(),
+            slashed
+        ),
+        Some(stake_mint) => {
+            let (Some(mint), Some(stake_token_account), Some(treasury_token_account), Some(token_program)) = (
+                accounts.mint.as_ref(),
+                accounts.stake_token_account.as_ref(),
+                accounts.treasury_token_account.as_ref(),
+                accounts.token_program.as_ref()
+            ) else {
+                return Err(ErrorCode::MissingTokenAccount.into());
+            };
+            require!(mint.key() == stake_mint, ErrorCode::AccountMismatch);
+
+            let world_key = accounts.world.key();
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"stake",
+                world_key.as_ref(),
+                stake.authority.as_ref(),
+                &[ctx.bumps.stake],
+            ]];
+            transfer_tokens(
+                stake_token_account.to_account_info(),
+                treasury_token_account.to_account_info(),
+                stake.to_account_info(),
+                mint,
+                token_program.to_account_info(),
+                slashed,
+                signer_seeds
+            )
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::World;
+use crate::state::world::ResolutionChange;
+use crate::state::trixel_data::SplitRule;
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct StartResolutionChangeArgs {
+    pub target_resolution: u8,  // One level finer or coarser than the canonical resolution
+    pub split_rule: SplitRule,  // How refining shares each leaf's value among its children
+}
+
+#[derive(Accounts)]
+pub struct StartResolutionChangeCtx<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAction
+    )]
+    pub world: Account<'info, World>,
+}
+
+/// Starts re-targeting a world to a new canonical resolution. Writes are rejected
+/// until finish_resolution_change, after retarget_trixels has moved the leaves
+/// and rehash_trixels has rebuilt the trixels above them
+pub fn handle_start_resolution_change(ctx: Context<StartResolutionChangeCtx>, args: StartResolutionChangeArgs) -> Result<()> {
+    ctx.accounts.world.start_resolution_change(ResolutionChange {
+        target_resolution: args.target_resolution,
+        split_rule: args.split_rule,
+    })
+}
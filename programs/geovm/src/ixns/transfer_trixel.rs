@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::state::{Trixel, World};
+use crate::errors::ErrorCode;
+use crate::helpers::ownership::count_owned_descendant;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TransferTrixelArgs {
+    pub id: u64,
+    pub new_owner: Option<Pubkey>,  // None relinquishes the trixel
+}
+
+#[derive(Accounts)]
+#[instruction(args: TransferTrixelArgs)]
+pub struct TransferTrixelCtx<'info> {
+    pub owner: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        constraint = trixel.load()?.owner() == Some(owner.key()) @ ErrorCode::NotTrixelOwner,
+        seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump = trixel.load()?.bump
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+}
+
+/// Hands the trixel to a new owner, or relinquishes it. Relinquishing takes the
+/// trixel's ancestors as remaining accounts (closest first), as they stop counting
+/// it among their owned descendants
+pub fn handle_transfer_trixel<'info>(
+    ctx: Context<'_, '_, 'info, 'info, TransferTrixelCtx<'info>>,
+    args: TransferTrixelArgs
+) -> Result<()> {
+    if args.new_owner.is_none() {
+        count_owned_descendant(&ctx.accounts.world, args.id, ctx.remaining_accounts, false)?;
+    }
+    ctx.accounts.trixel.load_mut()?.set_owner(args.new_owner);
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use crate::state::{Stake, Trixel, World};
+use crate::errors::ErrorCode;
+use crate::helpers::reporter::PreviousWrite;
+use crate::helpers::writes::*;
+use crate::helpers::htm::{get_trixel_id, verify_trixel_bump, SphericalCoords};
+use super::update_trixel::apply_trixel_update;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UpdateAtPointArgs {
+    pub coords: SphericalCoords,  // The point to update, resolved to the world's canonical trixel on-chain
+    pub value: i32,  // The value to add/set to the trixel and affect its ancestors
+}
+
+#[derive(Accounts)]
+#[instruction(args: UpdateAtPointArgs)]
+pub struct UpdateAtPointCtx<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = world.authority == payer.key() || !world.permissioned_updates @ ErrorCode::UnauthorizedAction,
+        constraint = world.authority == payer.key() || Stake::covers(write.stake.as_ref(), &world, payer.key()) @ ErrorCode::InsufficientStake
+    )]
+    pub world: Account<'info, World>,
+
+    // Checked against the trixel derived from the coordinates in the handler
+    #[account(
+        mut,
+        has_one = world
+    )]
+    pub trixel: AccountLoader<'info, Trixel>,
+
+    pub system_program: Program<'info, System>,
+
+    pub write: WriteAccounts<'info>,
+}
+
+pub fn handle_update_at_point<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateAtPointCtx<'info>>,
+    args: UpdateAtPointArgs
+) -> Result<()> {
+    // Worlds using commit-reveal only accept values through reveal_trixel_update
+    require!(
+        ctx.accounts.world.reveal_window == 0,
+        ErrorCode::CommitRevealRequired
+    );
+
+    // Derive the canonical trixel containing the point, the ancestors passed as
+    // remaining accounts are then checked against it by apply_trixel_update
+    let resolution = ctx.accounts.world.canonical_resolution;
+    let id = get_trixel_id(args.coords, resolution)?;
+    verify_trixel_bump(
+        &ctx.accounts.trixel.to_account_info(),
+        id,
+        &ctx.accounts.world.key(),
+        ctx.accounts.trixel.load()?.bump
+    )?;
+
+    ctx.accounts.write.reporter.record_write(&ctx.accounts.world, &ctx.accounts.payer)?;
+
+    ctx.accounts.write.fee.collect(
+        &ctx.accounts.world,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        resolution
+    )?;
+
+    let accounts = &mut *ctx.accounts;
+    let mut trixel = accounts.trixel.load_mut()?;
+    let previous_data = trixel.data()?;
+    let previous = PreviousWrite {
+        updates: trixel.updates,
+        last_update: trixel.last_update,
+    };
+    let roll_up = apply_trixel_update(
+        &mut accounts.world,
+        &mut trixel,
+        ctx.remaining_accounts,
+        &accounts.write.updater(&accounts.payer),
+        id,
+        args.value
+    )?;
+    drop(trixel);
+
+    accounts.write.finish_write(
+        &accounts.world,
+        &accounts.payer,
+        &accounts.trixel,
+        &accounts.system_program,
+        AppliedWrite {
+            id,
+            resolution,
+            value: args.value,
+            previous,
+            previous_data,
+            roll_up,
+        }
+    )
+}
@@ -1,14 +1,19 @@
 use anchor_lang::prelude::*;
-use crate::state::{Trixel, World, TrixelData};
-use crate::state::trixel_data::TrixelDataType;
+use crate::state::{Stake, Trixel, World, TrixelData};
+use crate::state::trixel_data::{RollUp, TrixelDataType};
 use crate::errors::ErrorCode;
-use crate::helpers::htm::{get_trixel_ancestors, resolution_from_trixel_id, get_child_index, SphericalCoords, get_trixel_id};
+use crate::helpers::reporter::PreviousWrite;
+use crate::helpers::writes::*;
+use crate::helpers::ownership::TrixelUpdater;
+use crate::helpers::htm::{resolution_from_trixel_id, get_child_index, SphericalCoords, get_trixel_id, verify_trixel_bump};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct UpdateTrixelArgs {
     pub id: u64,
     pub value: i32,  // The value to add/set to the trixel and affect its ancestors
     pub coords: Option<SphericalCoords>,  // Optional coordinates to verify the trixel ID
+    pub expected_updates: Option<u64>,  // Fails with TrixelChanged unless the trixel's update count matches
+    pub expected_hash: Option<[u8;32]>,  // Fails with TrixelChanged unless the trixel's hash matches
 }
 
 #[derive(Accounts)]
@@ -19,7 +24,8 @@ pub struct UpdateTrixelCtx<'info> {
     
     #[account(
         mut,
-        constraint = world.authority == payer.key() || !world.permissioned_updates @ ErrorCode::UnauthorizedAction 
+        constraint = world.authority == payer.key() || !world.permissioned_updates @ ErrorCode::UnauthorizedAction,
+        constraint = world.authority == payer.key() || Stake::covers(write.stake.as_ref(), &world, payer.key()) @ ErrorCode::InsufficientStake
     )]
     pub world: Account<'info, World>,
 
@@ -27,115 +33,252 @@ pub struct UpdateTrixelCtx<'info> {
         mut,
         has_one = world,
         seeds = [b"trixel", world.key().as_ref(), args.id.to_le_bytes().as_ref()],
-        bump
+        bump = trixel.load()?.bump
     )]
-    pub trixel: Account<'info, Trixel>,
+    pub trixel: AccountLoader<'info, Trixel>,
 
     /// CHECK: This is the system program
     pub system_program: Program<'info, System>,
+
+    pub write: WriteAccounts<'info>,
 }
 
 pub fn handle_update_trixel<'info>(
     ctx: Context<'_, '_, 'info, 'info, UpdateTrixelCtx<'info>>, 
     args: UpdateTrixelArgs
 ) -> Result<()> {
-    // Get world key before mutable borrow
-    let world_key = ctx.accounts.world.key();
-
-    // Dereference world and trixel accounts for easier access to their fields/methods
-    let world = &mut ctx.accounts.world;
-    let trixel = &mut ctx.accounts.trixel;
-
-    // 1. Preliminaries
-    let world_data_type = world.data.to_data_type();
+    // Worlds using commit-reveal only accept values through reveal_trixel_update
+    require!(
+        ctx.accounts.world.reveal_window == 0,
+        ErrorCode::CommitRevealRequired
+    );
 
     // If coordinates are provided, verify they match the trixel ID
     if let Some(coords) = args.coords {
-        let expected_id = get_trixel_id(coords, world.canonical_resolution)?;
+        let expected_id = get_trixel_id(coords, resolution_from_trixel_id(args.id)?)?;
         require!(
             expected_id == args.id,
             ErrorCode::InvalidTrixelId
         );
     }
 
-    // Check that the resolution of the trixel is the world's canonical resolution
+    // Compare-and-swap, lets clients detect writes made since they read the trixel
+    if let Some(expected_updates) = args.expected_updates {
+        require!(ctx.accounts.trixel.load()?.updates == expected_updates, ErrorCode::TrixelChanged);
+    }
+    if let Some(expected_hash) = args.expected_hash {
+        require!(ctx.accounts.trixel.load()?.hash == expected_hash, ErrorCode::TrixelChanged);
+    }
+
+    ctx.accounts.write.reporter.record_write(&ctx.accounts.world, &ctx.accounts.payer)?;
+
     let trixel_resolution = resolution_from_trixel_id(args.id)?;
+    ctx.accounts.write.fee.collect(
+        &ctx.accounts.world,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        trixel_resolution
+    )?;
+
+    let accounts = &mut *ctx.accounts;
+    let mut trixel = accounts.trixel.load_mut()?;
+    let previous_data = trixel.data()?;
+    let previous = PreviousWrite {
+        updates: trixel.updates,
+        last_update: trixel.last_update,
+    };
+    let roll_up = apply_trixel_update(
+        &mut accounts.world,
+        &mut trixel,
+        ctx.remaining_accounts,
+        &accounts.write.updater(&accounts.payer),
+        args.id,
+        args.value
+    )?;
+    drop(trixel);
+
+    accounts.write.finish_write(
+        &accounts.world,
+        &accounts.payer,
+        &accounts.trixel,
+        &accounts.system_program,
+        AppliedWrite {
+            id: args.id,
+            resolution: trixel_resolution,
+            value: args.value,
+            previous,
+            previous_data,
+            roll_up,
+        }
+    )
+}
+
+/// Applies `value` to the leaf trixel `id` and rolls the change up through
+/// its ancestors (passed in order from closest to furthest) and the world
+///
+/// If the trixel or any of its ancestors is owned, `updater` must hold one of
+/// them. Returns the roll-up applied to the ancestors and the world
+pub fn apply_trixel_update<'info>(
+    world: &mut Account<'info, World>,
+    trixel: &mut Trixel,
+    ancestor_accounts: &'info [AccountInfo<'info>],
+    updater: &TrixelUpdater,
+    id: u64,
+    value: i32
+) -> Result<RollUp> {
+    apply_trixel_write(
+        world,
+        trixel,
+        ancestor_accounts,
+        updater,
+        TrixelWrite { id, value: value.into(), points: 1 }
+    )
+}
+
+/// One or more points written to a leaf trixel, folded into a single write
+#[derive(Clone, Copy, Debug)]
+pub struct TrixelWrite {
+    pub id: u64,
+    pub value: i64,  // The sum of the points for accumulate types, the last point for overwrite types
+    pub points: u32,  // The number of points, added to the count of count types
+}
+
+impl TrixelWrite {
+    /// Folds another point into the write the way applying it afterwards would
+    pub fn add_point(&mut self, data_type: TrixelDataType, value: i32) -> Result<()> {
+        match data_type {
+            TrixelDataType::AggregateAccumulate | TrixelDataType::MeanAccumulate => {
+                self.value = self.value.checked_add(value.into()).ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+            _ => self.value = value.into(),
+        }
+        self.points = self.points.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// Applies a write to its leaf trixel and rolls the change up through the
+/// trixel's ancestors and the world, as apply_trixel_update does for a single value
+pub fn apply_trixel_write<'info>(
+    world: &mut Account<'info, World>,
+    trixel: &mut Trixel,
+    ancestor_accounts: &'info [AccountInfo<'info>],
+    updater: &TrixelUpdater,
+    write: TrixelWrite
+) -> Result<RollUp> {
+    let TrixelWrite { id, value, points } = write;
+
+    // Get world key before mutable borrow
+    let world_key = world.key();
+
+    // 1. Preliminaries
+    let world_data_type = world.data.to_data_type();
+    require!(!world.paused, ErrorCode::WorldPaused);
+    require!(world.resolution_change.is_none(), ErrorCode::ResolutionChangeInProgress);
+    require!(!trixel.is_frozen(), ErrorCode::TrixelFrozen);
+
+    // Owning a trixel grants rights over its whole subtree, so the updater must
+    // hold one of the owners along the ancestor chain, if there are any
+    let mut owned = trixel.owner().is_some();
+    let mut authorized = updater.owns(trixel.owner());
+
+    // Check that the world accepts writes at the trixel's resolution, and that
+    // the trixel is a leaf, as roll-ups from descendants would mix with the write
+    world.check_resolution(resolution_from_trixel_id(id)?)?;
+    require!(!trixel.has_children(), ErrorCode::ResolutionConflict);
+    // Callers derive the trixel from its seeds, but check it as this is shared
     require!(
-        trixel_resolution == world.canonical_resolution,
-        ErrorCode::InvalidResolution
+        trixel.world == world_key && trixel.id == id,
+        ErrorCode::AccountMismatch
     );
-    // The has_one = world constraint on the trixel account handles this check implicitly.
-    // require!(trixel.world == world.key(), ErrorCode::AccountMismatch); // world.key() is not available on World, but on Account<World>
-                                                                       // This is checked by has_one constraint.
+
+    // Throttle repeated writes to the same trixel
+    if trixel.updates > 0 {
+        let elapsed = Clock::get()?.unix_timestamp.saturating_sub(trixel.last_update);
+        require!(
+            elapsed >= world.min_update_interval,
+            ErrorCode::UpdateTooFrequent
+        );
+    }
 
     // Derive the list of ancestors of the trixel
-    let ancestors = get_trixel_ancestors(args.id)?;
+    world.check_bounds(id)?;
+    let ancestors = world.trixel_ancestors(id)?;
 
     // Verify we have the correct number of remaining accounts
     require!(
-        ancestors.len() == ctx.remaining_accounts.len(),
+        ancestors.len() == ancestor_accounts.len(),
         ErrorCode::InvalidArgument
     );
 
     // 2. Canonical Trixel Update
-    let mut update_delta_for_parent: i64 = 0;
-    let mut denominator_activated_by_canonical_trixel: bool = false;
-    let canonical_trixel_id = args.id;
+    let update_delta_for_parent: i64;
+    let denominator_activated_by_canonical_trixel: bool;
+    let canonical_trixel_id = id;
+    let mut data = trixel.data()?;
 
     match world_data_type {
         TrixelDataType::Count => {
-            if let TrixelData::Count { count } = &mut trixel.data {
-                *count = count.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+            if let TrixelData::Count { count } = &mut data {
+                *count = count.checked_add(points).ok_or(ErrorCode::ArithmeticOverflow)?;
             } else { return Err(ErrorCode::AccountMismatch.into()); }
-            update_delta_for_parent = 1;
+            update_delta_for_parent = points.into();
             denominator_activated_by_canonical_trixel = false;
         }
         TrixelDataType::AggregateOverwrite => {
-            require!(args.value >= 0, ErrorCode::InvalidArgument);
-            if let TrixelData::AggregateOverwrite { metric } = &mut trixel.data {
+            require!(value >= 0, ErrorCode::InvalidArgument);
+            if let TrixelData::AggregateOverwrite { metric } = &mut data {
                 let old_metric = *metric;
-                *metric = args.value as u64;
+                *metric = value as u64;
                 update_delta_for_parent = (*metric as i64) - (old_metric as i64);
             } else { return Err(ErrorCode::AccountMismatch.into()); }
             denominator_activated_by_canonical_trixel = false;
         }
         TrixelDataType::AggregateAccumulate => {
-            if let TrixelData::AggregateAccumulate { metric } = &mut trixel.data {
-                if args.value >= 0 {
-                    *metric = metric.checked_add(args.value as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
+            if let TrixelData::AggregateAccumulate { metric } = &mut data {
+                if value >= 0 {
+                    *metric = metric.checked_add(value as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
                 } else {
-                    *metric = metric.checked_sub(args.value.abs() as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
+                    *metric = metric.checked_sub(value.unsigned_abs()).ok_or(ErrorCode::ArithmeticOverflow)?;
                 }
-                update_delta_for_parent = args.value as i64;
+                update_delta_for_parent = value;
             } else { return Err(ErrorCode::AccountMismatch.into()); }
             denominator_activated_by_canonical_trixel = false;
         }
         TrixelDataType::MeanOverwrite => {
-            require!(args.value >= 0, ErrorCode::InvalidArgument);
-            if let TrixelData::MeanOverwrite { numerator, denominator } = &mut trixel.data {
+            require!(value >= 0, ErrorCode::InvalidArgument);
+            if let TrixelData::MeanOverwrite { numerator, denominator } = &mut data {
                 let old_numerator = *numerator;
                 let old_denominator_val = *denominator;
-                *numerator = args.value as u64;
+                *numerator = value as u64;
                 *denominator = 1; 
                 update_delta_for_parent = (*numerator as i64) - (old_numerator as i64);
                 denominator_activated_by_canonical_trixel = old_denominator_val == 0 && *denominator == 1;
             } else { return Err(ErrorCode::AccountMismatch.into()); }
         }
         TrixelDataType::MeanAccumulate => {
-            if let TrixelData::MeanAccumulate { numerator, denominator } = &mut trixel.data {
+            if let TrixelData::MeanAccumulate { numerator, denominator } = &mut data {
                 let old_denominator_val = *denominator;
-                if args.value >= 0 {
-                    *numerator = numerator.checked_add(args.value as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
+                if value >= 0 {
+                    *numerator = numerator.checked_add(value as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
                 } else {
-                    *numerator = numerator.checked_sub(args.value.abs() as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
+                    *numerator = numerator.checked_sub(value.unsigned_abs()).ok_or(ErrorCode::ArithmeticOverflow)?;
                 }
                 *denominator = 1; 
-                update_delta_for_parent = args.value as i64;
+                update_delta_for_parent = value;
                 denominator_activated_by_canonical_trixel = old_denominator_val == 0 && *denominator == 1;
             } else { return Err(ErrorCode::AccountMismatch.into()); }
         }
     }
 
+    let roll_up = RollUp {
+        count: if world_data_type == TrixelDataType::Count { points.into() } else { 0 },
+        metric: update_delta_for_parent,
+        denominator: i64::from(denominator_activated_by_canonical_trixel),
+    };
+
+    trixel.set_data(data);
+
     trixel.last_update = Clock::get()?.unix_timestamp;
     trixel.updates = trixel.updates.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
     
@@ -143,106 +286,38 @@ pub fn handle_update_trixel<'info>(
     let (mut prev_child_idx, _) = get_child_index(canonical_trixel_id)?;
 
     // 3. Ancestor Trixel Updates (Loop)
-    for (i, rem_acc) in ctx.remaining_accounts.iter().enumerate() {
+    for (i, rem_acc) in ancestor_accounts.iter().enumerate() {
         let ancestor_id = ancestors[i];
-        let ancestor_id_bytes = ancestor_id.to_le_bytes();
-
-        let (ancestor_pda, _ancestor_bump) = Pubkey::find_program_address(
-            &[
-                b"trixel",
-                world_key.as_ref(), // Use world_key here
-                ancestor_id_bytes.as_ref()
-            ],
-            ctx.program_id
-        );
-        require!(rem_acc.key() == ancestor_pda, ErrorCode::InvalidTrixelAccount);
 
-        let mut ancestor = Account::<'info, Trixel>::try_from(rem_acc)?;
+        let ancestor_loader = AccountLoader::<'info, Trixel>::try_from(rem_acc)?;
+        let mut ancestor = ancestor_loader.load_mut()?;
+        verify_trixel_bump(rem_acc, ancestor_id, &world_key, ancestor.bump)?;
         // require!(ancestor.world == ctx.accounts.world.key(), ErrorCode::AccountMismatch); // checked by has_one on trixel, ancestor is not has_one
                                                                                           // instead check ancestor.world == world.key() where world is the dereferenced Account<World>
         require!(ancestor.world == world_key, ErrorCode::AccountMismatch); // Use world_key here
-        require!(ancestor.data.to_data_type() == world_data_type, ErrorCode::AccountMismatch);
-
-        match world_data_type {
-            TrixelDataType::Count => {
-                if let TrixelData::Count { count } = &mut ancestor.data {
-                    *count = count.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
-                } else { return Err(ErrorCode::AccountMismatch.into()); }
-            }
-            TrixelDataType::AggregateOverwrite | TrixelDataType::AggregateAccumulate => {
-                let current_metric = match &mut ancestor.data {
-                    TrixelData::AggregateOverwrite { metric } => metric,
-                    TrixelData::AggregateAccumulate { metric } => metric,
-                    _ => return Err(ErrorCode::AccountMismatch.into()),
-                };
-                if update_delta_for_parent >= 0 {
-                    *current_metric = current_metric.checked_add(update_delta_for_parent as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
-                } else {
-                    *current_metric = current_metric.checked_sub(update_delta_for_parent.abs() as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
-                }
-            }
-            TrixelDataType::MeanOverwrite | TrixelDataType::MeanAccumulate => {
-                let (current_numerator, current_denominator) = match &mut ancestor.data {
-                    TrixelData::MeanOverwrite { numerator, denominator } => (numerator, denominator),
-                    TrixelData::MeanAccumulate { numerator, denominator } => (numerator, denominator),
-                    _ => return Err(ErrorCode::AccountMismatch.into()),
-                };
-                if update_delta_for_parent >= 0 {
-                    *current_numerator = current_numerator.checked_add(update_delta_for_parent as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
-                } else {
-                    *current_numerator = current_numerator.checked_sub(update_delta_for_parent.abs() as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
-                }
-                if denominator_activated_by_canonical_trixel {
-                    *current_denominator = current_denominator.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
-                }
-            }
+        require!(ancestor.data()?.to_data_type() == world_data_type, ErrorCode::AccountMismatch);
+        require!(!ancestor.is_frozen(), ErrorCode::TrixelFrozen);
+        if ancestor.owner().is_some() {
+            owned = true;
+            authorized |= updater.owns(ancestor.owner());
         }
 
+        ancestor.roll_up(&roll_up)?;
+
         ancestor.last_update = Clock::get()?.unix_timestamp;
         ancestor.updates = ancestor.updates.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
         prev_hash = ancestor.update_child_hash(prev_child_idx, prev_hash)?;
         (prev_child_idx, _) = get_child_index(ancestor.id)?;
-        ancestor.exit(ctx.program_id)?;
     }
 
+    require!(!owned || authorized, ErrorCode::NotTrixelOwner);
+
     // 4. World Account Update
-    match world_data_type {
-        TrixelDataType::Count => {
-            if let TrixelData::Count { count } = &mut world.data {
-                *count = count.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
-            } else { return Err(ErrorCode::AccountMismatch.into()); }
-        }
-        TrixelDataType::AggregateOverwrite | TrixelDataType::AggregateAccumulate => {
-             let world_metric = match &mut world.data {
-                TrixelData::AggregateOverwrite { metric } => metric,
-                TrixelData::AggregateAccumulate { metric } => metric,
-                _ => return Err(ErrorCode::AccountMismatch.into()),
-            };
-            if update_delta_for_parent >= 0 {
-                *world_metric = world_metric.checked_add(update_delta_for_parent as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
-            } else {
-                *world_metric = world_metric.checked_sub(update_delta_for_parent.abs() as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
-            }
-        }
-        TrixelDataType::MeanOverwrite | TrixelDataType::MeanAccumulate => {
-            let (world_numerator, world_denominator) = match &mut world.data {
-                TrixelData::MeanOverwrite { numerator, denominator } => (numerator, denominator),
-                TrixelData::MeanAccumulate { numerator, denominator } => (numerator, denominator),
-                _ => return Err(ErrorCode::AccountMismatch.into()),
-            };
-            if update_delta_for_parent >= 0 {
-                *world_numerator = world_numerator.checked_add(update_delta_for_parent as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
-            } else {
-                *world_numerator = world_numerator.checked_sub(update_delta_for_parent.abs() as u64).ok_or(ErrorCode::ArithmeticOverflow)?;
-            }
-            if denominator_activated_by_canonical_trixel {
-                *world_denominator = world_denominator.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
-            }
-        }
-    }
+    world.data.roll_up(&roll_up)?;
     
     world.updates = world.updates.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
-    world.update_child_hash_and_root(prev_child_idx, prev_hash)?;
+    let root_idx = world.root_index(ancestors.last().copied().unwrap_or(id))?;
+    world.update_child_hash_and_root(&world_key, root_idx, prev_hash)?;
 
-    Ok(())
+    Ok(roll_up)
 } 
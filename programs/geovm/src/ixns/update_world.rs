@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::state::World;
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UpdateWorldArgs {
+    pub reveal_window: Option<i64>,  // Seconds a commitment stays revealable, 0 disables commit-reveal
+    pub min_update_interval: Option<i64>,  // Minimum seconds between writes to the same trixel
+    pub max_updates_per_epoch: Option<u32>,  // Maximum writes per updater per epoch, 0 is unlimited
+    pub paused: Option<bool>,  // Emergency stop for all trixel writes
+    pub claims_enabled: Option<bool>,  // Lets updaters claim ownership of trixels
+    pub challenge_window: Option<i64>,  // Seconds a write can be challenged for, 0 disables disputes
+    pub dispute_bond: Option<u64>,  // Lamports bonded by updaters per write, and the minimum challenge bond
+    pub arbiter: Option<Pubkey>,  // May resolve challenges and slash stakes alongside the authority, the default key clears it
+    pub min_stake: Option<u64>,  // Stake required of updaters other than the authority, 0 disables staking
+    pub stake_mint: Option<Pubkey>,  // Token staked by updaters, the default key stakes lamports
+    pub unbonding_period: Option<i64>,  // Seconds unstaked funds stay slashable before they can be withdrawn
+    pub variable_resolution: Option<bool>,  // Accepts writes to trixels coarser than the canonical resolution
+}
+
+#[derive(Accounts)]
+pub struct UpdateWorldCtx<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAction
+    )]
+    pub world: Account<'info, World>,
+}
+
+pub fn handle_update_world(ctx: Context<UpdateWorldCtx>, args: UpdateWorldArgs) -> Result<()> {
+    let world = &mut ctx.accounts.world;
+
+    if let Some(reveal_window) = args.reveal_window {
+        require!(reveal_window >= 0, ErrorCode::InvalidArgument);
+        world.reveal_window = reveal_window;
+    }
+
+    if let Some(min_update_interval) = args.min_update_interval {
+        require!(min_update_interval >= 0, ErrorCode::InvalidArgument);
+        world.min_update_interval = min_update_interval;
+    }
+
+    if let Some(max_updates_per_epoch) = args.max_updates_per_epoch {
+        world.max_updates_per_epoch = max_updates_per_epoch;
+    }
+
+    if let Some(paused) = args.paused {
+        world.paused = paused;
+    }
+
+    if let Some(claims_enabled) = args.claims_enabled {
+        world.claims_enabled = claims_enabled;
+    }
+
+    if let Some(challenge_window) = args.challenge_window {
+        require!(challenge_window >= 0, ErrorCode::InvalidArgument);
+        world.challenge_window = challenge_window;
+    }
+
+    if let Some(dispute_bond) = args.dispute_bond {
+        world.dispute_bond = dispute_bond;
+    }
+
+    if let Some(arbiter) = args.arbiter {
+        world.arbiter = (arbiter != Pubkey::default()).then_some(arbiter);
+    }
+
+    if let Some(min_stake) = args.min_stake {
+        world.min_stake = min_stake;
+    }
+
+    if let Some(stake_mint) = args.stake_mint {
+        world.stake_mint = (stake_mint != Pubkey::default()).then_some(stake_mint);
+    }
+
+    if let Some(unbonding_period) = args.unbonding_period {
+        require!(unbonding_period >= 0, ErrorCode::InvalidArgument);
+        world.unbonding_period = unbonding_period;
+    }
+
+    if let Some(variable_resolution) = args.variable_resolution {
+        world.variable_resolution = variable_resolution;
+    }
+
+    Ok(())
+}
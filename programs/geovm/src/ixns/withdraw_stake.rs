@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Stake, World};
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::{transfer_tokens, withdraw_lamports};
+
+#[derive(Accounts)]
+pub struct WithdrawStakeCtx<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        constraint = stake.authority == staker.key() @ ErrorCode::UnauthorizedAction,
+        seeds = [b"stake", world.key().as_ref(), staker.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, Stake>,
+
+    // Token stakes only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = stake
+    )]
+    pub stake_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub staker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+/// Withdraws the unbonding part of a stake once the world's unbonding period has passed
+pub fn handle_withdraw_stake(ctx: Context<WithdrawStakeCtx>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let amount = accounts.stake.unbonding_amount;
+    require!(amount > 0, ErrorCode::InvalidArgument);
+
+    let unbonded_at = accounts.stake.unbonding_since
+        .checked_add(accounts.world.unbonding_period)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(Clock::get()?.unix_timestamp >= unbonded_at, ErrorCode::StakeUnbonding);
+
+    match accounts.stake.mint {
+        None => withdraw_lamports(
+            &accounts.stake.to_account_info(),
+            &accounts.staker.to_account_info(),
+            amount
+        )?,
+        Some(stake_mint) => {
+            let (Some(mint), Some(stake_token_account), Some(staker_token_account), Some(token_program)) = (
+                accounts.mint.as_ref(),
+                accounts.stake_token_account.as_ref(),
+                accounts.staker_token_account.as_ref(),
+                accounts.token_program.as_ref()
+            ) else {
+                return Err(ErrorCode::MissingTokenAccount.into());
+            };
+            require!(mint.key() == stake_mint, ErrorCode::AccountMismatch);
+
+            let world_key = accounts.world.key();
+            let staker_key = accounts.staker.key();
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"stake",
+                world_key.as_ref(),
+                staker_key.as_ref(),
+                &[ctx.bumps.stake],
+            ]];
+            transfer_tokens(
+                stake_token_account.to_account_info(),
+                staker_token_account.to_account_info(),
+                accounts.stake.to_account_info(),
+                mint,
+                token_program.to_account_info(),
+                amount,
+                signer_seeds
+            )?;
+        }
+    }
+
+    ctx.accounts.stake.unbonding_amount = 0;
+    Ok(())
+}
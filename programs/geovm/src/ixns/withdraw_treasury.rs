@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Treasury, World};
+use crate::errors::ErrorCode;
+use crate::helpers::transfers::{transfer_tokens, withdraw_lamports};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct WithdrawTreasuryArgs {
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryCtx<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority @ ErrorCode::UnauthorizedAction
+    )]
+    pub world: Account<'info, World>,
+
+    #[account(
+        mut,
+        has_one = world,
+        seeds = [b"treasury", world.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// CHECK: Receives lamport withdrawals, chosen by the authority
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    // Token withdrawals only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = treasury
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub destination_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn handle_withdraw_treasury(ctx: Context<WithdrawTreasuryCtx>, args: WithdrawTreasuryArgs) -> Result<()> {
+    let accounts = &ctx.accounts;
+
+    // Without a mint this is a lamport withdrawal from the treasury itself
+    let Some(mint) = accounts.mint.as_ref() else {
+        return withdraw_lamports(
+            &accounts.treasury.to_account_info(),
+            &accounts.destination.to_account_info(),
+            args.amount
+        );
+    };
+
+    let (Some(treasury_token_account), Some(destination_token_account), Some(token_program)) = (
+        accounts.treasury_token_account.as_ref(),
+        accounts.destination_token_account.as_ref(),
+        accounts.token_program.as_ref()
+    ) else {
+        return Err(ErrorCode::MissingTokenAccount.into());
+    };
+
+    let world_key = accounts.world.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"treasury",
+        world_key.as_ref(),
+        &[ctx.bumps.treasury],
+    ]];
+    transfer_tokens(
+        treasury_token_account.to_account_info(),
+        destination_token_account.to_account_info(),
+        accounts.treasury.to_account_info(),
+        mint,
+        token_program.to_account_info(),
+        args.amount,
+        signer_seeds
+    )
+}
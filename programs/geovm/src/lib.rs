@@ -3,9 +3,12 @@ use anchor_lang::prelude::*;
 mod errors;
 mod ixns;
 mod state;
-mod helpers;
+pub mod helpers;
+#[cfg(feature = "cpi")]
+pub mod reader;
 
 pub use ixns::*;
+pub use state::TrixelData;
 
 declare_id!("2v5V4aVkQnFHojUoY4td6X7E7e5NQ78mKzAjAoA6JBrJ");
 
@@ -25,5 +28,157 @@ pub mod geovm {
         handle_update_trixel(ctx, args)
     }
 
+    pub fn update_world(ctx: Context<UpdateWorldCtx>, args: UpdateWorldArgs) -> Result<()> {
+        handle_update_world(ctx, args)
+    }
+
+    pub fn commit_trixel_update(ctx: Context<CommitTrixelUpdateCtx>, args: CommitTrixelUpdateArgs) -> Result<()> {
+        handle_commit_trixel_update(ctx, args)
+    }
+
+    pub fn reveal_trixel_update<'info>(ctx: Context<'_, '_, 'info, 'info, RevealTrixelUpdateCtx<'info>>, args: RevealTrixelUpdateArgs) -> Result<()> {
+        handle_reveal_trixel_update(ctx, args)
+    }
+
+    pub fn set_fee_schedule(ctx: Context<SetFeeScheduleCtx>, args: SetFeeScheduleArgs) -> Result<()> {
+        handle_set_fee_schedule(ctx, args)
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasuryCtx>, args: WithdrawTreasuryArgs) -> Result<()> {
+        handle_withdraw_treasury(ctx, args)
+    }
+
+    pub fn register_reporter(ctx: Context<RegisterReporterCtx>) -> Result<()> {
+        handle_register_reporter(ctx)
+    }
+
+    pub fn create_bounty(ctx: Context<CreateBountyCtx>, args: CreateBountyArgs) -> Result<()> {
+        handle_create_bounty(ctx, args)
+    }
+
+    pub fn fund_bounty(ctx: Context<FundBountyCtx>, args: FundBountyArgs) -> Result<()> {
+        handle_fund_bounty(ctx, args)
+    }
+
+    pub fn close_bounty(ctx: Context<CloseBountyCtx>) -> Result<()> {
+        handle_close_bounty(ctx)
+    }
+
+    pub fn set_trixel_frozen(ctx: Context<SetTrixelFrozenCtx>, args: SetTrixelFrozenArgs) -> Result<()> {
+        handle_set_trixel_frozen(ctx, args)
+    }
+
+    pub fn claim_trixel<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimTrixelCtx<'info>>, args: ClaimTrixelArgs) -> Result<()> {
+        handle_claim_trixel(ctx, args)
+    }
+
+    pub fn transfer_trixel<'info>(ctx: Context<'_, '_, 'info, 'info, TransferTrixelCtx<'info>>, args: TransferTrixelArgs) -> Result<()> {
+        handle_transfer_trixel(ctx, args)
+    }
+
+    pub fn list_trixel(ctx: Context<ListTrixelCtx>, args: ListTrixelArgs) -> Result<()> {
+        handle_list_trixel(ctx, args)
+    }
+
+    pub fn buy_trixel(ctx: Context<BuyTrixelCtx>, args: BuyTrixelArgs) -> Result<()> {
+        handle_buy_trixel(ctx, args)
+    }
+
+    pub fn cancel_listing(ctx: Context<CancelListingCtx>, args: CancelListingArgs) -> Result<()> {
+        handle_cancel_listing(ctx, args)
+    }
+
+    pub fn place_bid(ctx: Context<PlaceBidCtx>, args: PlaceBidArgs) -> Result<()> {
+        handle_place_bid(ctx, args)
+    }
+
+    pub fn settle_auction(ctx: Context<SettleAuctionCtx>, args: SettleAuctionArgs) -> Result<()> {
+        handle_settle_auction(ctx, args)
+    }
+
+    pub fn mint_trixel_nft(ctx: Context<MintTrixelNftCtx>, args: MintTrixelNftArgs) -> Result<()> {
+        handle_mint_trixel_nft(ctx, args)
+    }
+
+    pub fn challenge_update(ctx: Context<ChallengeUpdateCtx>, args: ChallengeUpdateArgs) -> Result<()> {
+        handle_challenge_update(ctx, args)
+    }
+
+    pub fn resolve_challenge<'info>(ctx: Context<'_, '_, 'info, 'info, ResolveChallengeCtx<'info>>, args: ResolveChallengeArgs) -> Result<()> {
+        handle_resolve_challenge(ctx, args)
+    }
+
+    pub fn close_update_record(ctx: Context<CloseUpdateRecordCtx>) -> Result<()> {
+        handle_close_update_record(ctx)
+    }
+
+    pub fn deposit_stake(ctx: Context<DepositStakeCtx>, args: DepositStakeArgs) -> Result<()> {
+        handle_deposit_stake(ctx, args)
+    }
+
+    pub fn request_unstake(ctx: Context<RequestUnstakeCtx>, args: RequestUnstakeArgs) -> Result<()> {
+        handle_request_unstake(ctx, args)
+    }
+
+    pub fn withdraw_stake(ctx: Context<WithdrawStakeCtx>) -> Result<()> {
+        handle_withdraw_stake(ctx)
+    }
+
+    pub fn slash_stake(ctx: Context<SlashStakeCtx>, args: SlashStakeArgs) -> Result<()> {
+        handle_slash_stake(ctx, args)
+    }
+
+    pub fn get_trixel_value(ctx: Context<GetTrixelValueCtx>, args: GetTrixelValueArgs) -> Result<TrixelValue> {
+        handle_get_trixel_value(ctx, args)
+    }
+
+    pub fn update_at_point<'info>(ctx: Context<'_, '_, 'info, 'info, UpdateAtPointCtx<'info>>, args: UpdateAtPointArgs) -> Result<()> {
+        handle_update_at_point(ctx, args)
+    }
+
+    pub fn ingest_points<'info>(ctx: Context<'_, '_, 'info, 'info, IngestPointsCtx<'info>>, args: IngestPointsArgs) -> Result<()> {
+        handle_ingest_points(ctx, args)
+    }
+
+    pub fn get_region_value<'info>(ctx: Context<'_, '_, 'info, 'info, GetRegionValueCtx<'info>>, args: GetRegionValueArgs) -> Result<RegionValue> {
+        handle_get_region_value(ctx, args)
+    }
+
+    pub fn migrate_trixel(ctx: Context<MigrateTrixelCtx>, args: MigrateTrixelArgs) -> Result<()> {
+        handle_migrate_trixel(ctx, args)
+    }
+
+    pub fn create_named_world(ctx: Context<CreateNamedWorldCtx>, args: CreateWorldArgs) -> Result<()> {
+        handle_create_named_world(ctx, args)
+    }
+
+    pub fn set_world_metadata(ctx: Context<SetWorldMetadataCtx>, args: SetWorldMetadataArgs) -> Result<()> {
+        handle_set_world_metadata(ctx, args)
+    }
+
+    pub fn set_world_bounds(ctx: Context<SetWorldBoundsCtx>, args: SetWorldBoundsArgs) -> Result<()> {
+        handle_set_world_bounds(ctx, args)
+    }
+
+    pub fn start_resolution_change(ctx: Context<StartResolutionChangeCtx>, args: StartResolutionChangeArgs) -> Result<()> {
+        handle_start_resolution_change(ctx, args)
+    }
+
+    pub fn retarget_trixels<'info>(ctx: Context<'_, '_, 'info, 'info, RetargetTrixelsCtx<'info>>, args: RetargetTrixelsArgs) -> Result<()> {
+        handle_retarget_trixels(ctx, args)
+    }
+
+    pub fn rehash_trixels<'info>(ctx: Context<'_, '_, 'info, 'info, RehashTrixelsCtx<'info>>, args: RehashTrixelsArgs) -> Result<()> {
+        handle_rehash_trixels(ctx, args)
+    }
+
+    pub fn finish_resolution_change<'info>(ctx: Context<'_, '_, 'info, 'info, FinishResolutionChangeCtx<'info>>) -> Result<()> {
+        handle_finish_resolution_change(ctx)
+    }
+
+    pub fn migrate_world(ctx: Context<MigrateWorldCtx>) -> Result<()> {
+        handle_migrate_world(ctx)
+    }
+
 
 }
@@ -0,0 +1,28 @@
+//! Typed helpers for reading trixels from other programs, enabled with the `cpi` feature
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+use crate::errors::ErrorCode;
+use crate::{GetTrixelValueArgs, TrixelValue};
+
+/// Reads trixel `id` of `world` by calling `get_trixel_value`
+pub fn read_trixel_value<'info>(
+    geovm_program: AccountInfo<'info>,
+    world: AccountInfo<'info>,
+    trixel: AccountInfo<'info>,
+    id: u64
+) -> Result<TrixelValue> {
+    crate::cpi::get_trixel_value(
+        CpiContext::new(geovm_program, crate::cpi::accounts::GetTrixelValueCtx { world, trixel }),
+        GetTrixelValueArgs { id }
+    )?;
+    trixel_value_from_return_data()
+}
+
+/// Deserializes the return data left by a `get_trixel_value` call, checking it
+/// was set by this program
+pub fn trixel_value_from_return_data() -> Result<TrixelValue> {
+    let (program_id, data) = get_return_data().ok_or(ErrorCode::InvalidReturnData)?;
+    require_keys_eq!(program_id, crate::ID, ErrorCode::InvalidReturnData);
+    TrixelValue::try_from_slice(&data).map_err(|_| error!(ErrorCode::InvalidReturnData))
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+/// Which trixels a bounty pays out for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum BountyTarget {
+    Trixel {
+        id: u64
+    },
+    Resolution {
+        resolution: u8
+    },
+}
+
+/// Which writes to a targeted trixel earn the reward
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum BountyTrigger {
+    FirstWrite,
+    Cooldown {
+        seconds: i64  // Pays for a write if the trixel was last written at least this long ago
+    },
+}
+
+/// Rewards reporters for writing to a set of trixels, seeded by
+/// `[b"bounty", world, creator, bounty_id]`
+///
+/// Lamport rewards are held on this account directly, token rewards in a token
+/// account owned by it
+#[account]
+pub struct Bounty {
+    pub world: Pubkey,
+    pub creator: Pubkey,
+    pub bounty_id: u64,
+    pub target: BountyTarget,
+    pub trigger: BountyTrigger,
+    pub mint: Option<Pubkey>,
+    pub reward: u64,
+    pub payouts: u64,
+}
+
+impl Bounty {
+
+    pub fn bytes() -> usize {
+        8 + std::mem::size_of::<Bounty>()
+    }
+
+    /// Whether a write to trixel `id` pays out, given the trixel's state before the write
+    pub fn is_eligible(
+        &self,
+        id: u64,
+        resolution: u8,
+        previous_updates: u64,
+        previous_update_time: i64,
+        now: i64
+    ) -> bool {
+        let targeted = match self.target {
+            BountyTarget::Trixel { id: target_id } => target_id == id,
+            BountyTarget::Resolution { resolution: target_resolution } => target_resolution == resolution,
+        };
+        let triggered = match self.trigger {
+            BountyTrigger::FirstWrite => previous_updates == 0,
+            BountyTrigger::Cooldown { seconds } => {
+                previous_updates == 0 || now.saturating_sub(previous_update_time) >= seconds
+            }
+        };
+        targeted && triggered
+    }
+}
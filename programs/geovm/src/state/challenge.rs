@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+/// A dispute over a recorded write, seeded by `[b"challenge", update_record]`
+///
+/// The challenger's bond is held on this account until the world authority or
+/// arbiter resolves it
+#[derive(Default)]
+#[account]
+pub struct Challenge {
+    pub update_record: Pubkey,
+    pub challenger: Pubkey,
+    pub bond: u64,
+    pub created_at: i64,
+}
+
+impl Challenge {
+
+    pub fn bytes() -> usize {
+        8 + std::mem::size_of::<Challenge>()
+    }
+}
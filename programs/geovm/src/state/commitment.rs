@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+/// A hidden update to a trixel, published ahead of the value itself so the
+/// value cannot be copied by other reporters before it lands
+#[derive(Default)]
+#[account]
+pub struct TrixelCommitment {
+    pub world: Pubkey,
+    pub updater: Pubkey,
+    pub hash: [u8;32],
+    pub committed_at: i64,
+    pub committed_slot: u64,
+}
+
+impl TrixelCommitment {
+
+    pub fn bytes() -> usize {
+        8 + std::mem::size_of::<TrixelCommitment>()
+    }
+
+    pub fn init(
+        &mut self,
+        world: Pubkey,
+        updater: Pubkey,
+        hash: [u8;32]
+    ) -> Result<()> {
+        self.world = world;
+        self.updater = updater;
+        self.hash = hash;
+        let clock = Clock::get()?;
+        self.committed_at = clock.unix_timestamp;
+        self.committed_slot = clock.slot;
+        Ok(())
+    }
+
+    /// Computes the commitment hash for an update of `value` to trixel `id` of
+    /// `world` by `updater`. Binding the updater means a revealed salt can't be
+    /// reused to commit the same update under another key
+    pub fn compute_hash(world: &Pubkey, updater: &Pubkey, id: u64, value: i32, salt: &[u8; 32]) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hashv(&[
+            world.as_ref(),
+            updater.as_ref(),
+            &id.to_le_bytes(),
+            &value.to_le_bytes(),
+            salt
+        ]).to_bytes()
+    }
+}
@@ -0,0 +1,32 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// How an update fee scales with the trixel being written
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FeeScaling {
+    Fixed,
+    PerResolution,
+}
+
+/// Fee charged to the payer of each trixel write in a world
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FeeSchedule {
+    pub mint: Option<Pubkey>,  // SPL mint the fee is paid in, or lamports if None
+    pub amount: u64,
+    pub scaling: FeeScaling,
+}
+
+impl FeeSchedule {
+    /// Computes the fee for a write at the given trixel resolution
+    ///
+    /// `PerResolution` charges `amount` for every trixel touched by the roll-up,
+    /// i.e. the written trixel plus each of its `resolution` ancestors
+    pub fn fee_for_resolution(&self, resolution: u8) -> Result<u64> {
+        match self.scaling {
+            FeeScaling::Fixed => Ok(self.amount),
+            FeeScaling::PerResolution => self.amount
+                .checked_mul(resolution as u64 + 1)
+                .ok_or(ErrorCode::ArithmeticOverflow.into()),
+        }
+    }
+}
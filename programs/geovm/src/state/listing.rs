@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+/// How a listed trixel is sold
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ListingKind {
+    FixedPrice,
+    Auction {
+        end_time: i64  // Bids are accepted until this unix timestamp
+    },
+}
+
+/// A trixel offered for sale by its owner, seeded by `[b"listing", trixel]`
+///
+/// While listed the trixel is owned by this account, so it cannot be transferred
+/// or written to until the listing is bought, settled or cancelled. Auction bids
+/// are escrowed on this account directly, or in a token account owned by it
+///
+/// A minted trixel goes back to its token if the listing is cancelled or the
+/// auction gets no bids, but a sale hands it to the buyer's wallet
+#[account]
+pub struct Listing {
+    pub world: Pubkey,
+    pub trixel: Pubkey,
+    pub seller: Pubkey,
+    pub owner: Pubkey,  // The trixel's owner when listed, the seller or its token's mint, given back if unsold
+    pub mint: Option<Pubkey>,
+    pub price: u64,  // Sale price, or the reserve price for auctions
+    pub kind: ListingKind,
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,
+}
+
+impl Listing {
+
+    pub fn bytes() -> usize {
+        8 + std::mem::size_of::<Listing>()
+    }
+}
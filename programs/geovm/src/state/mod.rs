@@ -5,4 +5,34 @@ pub mod trixel;
 pub use trixel::Trixel;
 
 pub mod trixel_data;
-pub use trixel_data::TrixelData;
\ No newline at end of file
+pub use trixel_data::TrixelData;
+
+pub mod commitment;
+pub use commitment::TrixelCommitment;
+
+pub mod fee_schedule;
+pub use fee_schedule::FeeSchedule;
+
+pub mod treasury;
+pub use treasury::Treasury;
+
+pub mod bounty;
+pub use bounty::Bounty;
+
+pub mod reporter;
+pub use reporter::Reporter;
+
+pub mod listing;
+pub use listing::Listing;
+
+pub mod update_record;
+pub use update_record::UpdateRecord;
+
+pub mod challenge;
+pub use challenge::Challenge;
+
+pub mod stake;
+pub use stake::Stake;
+
+pub mod world_metadata;
+pub use world_metadata::WorldMetadata;
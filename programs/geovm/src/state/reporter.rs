@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// Per-world record of a reporter's activity, seeded by `[b"reporter", world, authority]`
+#[derive(Default)]
+#[account]
+pub struct Reporter {
+    pub world: Pubkey,
+    pub authority: Pubkey,
+    pub bounty_payouts: u64,
+    pub epoch: u64,
+    pub epoch_updates: u32,
+}
+
+impl Reporter {
+
+    pub fn bytes() -> usize {
+        8 + std::mem::size_of::<Reporter>()
+    }
+}
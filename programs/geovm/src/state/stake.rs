@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use super::world::World;
+
+/// A reporter's stake in a world, seeded by `[b"stake", world, authority]`
+///
+/// Lamport stakes are held on this account directly, token stakes in a token
+/// account owned by it. Unbonding stake no longer counts towards the world's
+/// minimum but can still be slashed until it is withdrawn
+#[derive(Default)]
+#[account]
+pub struct Stake {
+    pub world: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub unbonding_amount: u64,
+    pub unbonding_since: i64,
+}
+
+impl Stake {
+
+    pub fn bytes() -> usize {
+        8 + std::mem::size_of::<Stake>()
+    }
+
+    /// Whether `updater` has staked enough to write to `world`
+    pub fn covers(stake: Option<&Account<Stake>>, world: &Account<World>, updater: Pubkey) -> bool {
+        if world.min_stake == 0 {
+            return true;
+        }
+        stake.is_some_and(|stake| {
+            stake.world == world.key()
+                && stake.authority == updater
+                && stake.mint == world.stake_mint
+                && stake.amount >= world.min_stake
+        })
+    }
+}
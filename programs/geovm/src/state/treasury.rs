@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+/// Collects a world's update fees, seeded by `[b"treasury", world]`
+///
+/// Lamport fees are held on this account directly, token fees in token accounts
+/// owned by it
+#[derive(Default)]
+#[account]
+pub struct Treasury {
+    pub world: Pubkey,
+}
+
+impl Treasury {
+
+    pub fn bytes() -> usize {
+        8 + std::mem::size_of::<Treasury>()
+    }
+}
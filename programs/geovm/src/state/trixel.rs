@@ -1,12 +1,36 @@
 use crate::errors::ErrorCode;
 use anchor_lang::prelude::*;
-use super::trixel_data::{TrixelData, TrixelDataType};
+use super::trixel_data::{PackedTrixelData, RollUp, TrixelData, TrixelDataType};
 
+const TRIXEL_HASH_DOMAIN: &[u8] = b"geovm:trixel:v1";
 
 
-#[derive(Default)]
-#[account]
+
+/// Zero-copy, so writes update a trixel and its ancestors in place rather than
+/// deserializing and reserializing each of them. Seeds [b"trixel", world, id]
+///
+/// Its discriminator is that of a `TrixelV2` account, so trixels still in the
+/// legacy layout are rejected until migrate_trixel has rewritten them
+#[account(zero_copy, discriminator = [125, 54, 236, 23, 211, 88, 123, 130])]
 pub struct Trixel {
+    pub world: Pubkey,
+    pub id: u64,
+    pub updates: u64,
+    pub last_update: i64,
+    pub hash: [u8;32],
+    pub child_hashes: [[u8;32]; 4],
+    pub owner: Pubkey,  // The default key when unowned, see owner()
+    pub data: PackedTrixelData,  // See data()
+    pub resolution: u8,
+    pub frozen: u8,  // See is_frozen()
+    pub bump: u8,  // Stored so the address can be checked without searching for it
+    pub hash_version: u8,  // The world's hash version when the trixel was created
+    pub owned_descendants: u32,  // Owned trixels beneath this one, which keep it from being claimed
+}
+
+/// The Borsh layout trixels had before they were zero-copy, read by migrate_trixel
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyTrixel {
     pub world: Pubkey,
     pub id: u64,
     pub resolution: u8,
@@ -14,17 +38,76 @@ pub struct Trixel {
     pub last_update: i64,
     pub hash: [u8;32],
     pub child_hashes: [[u8;32]; 4],
-    pub data: TrixelData
+    pub data: TrixelData,
+    pub frozen: bool,
+    pub owner: Option<Pubkey>
+}
+
+impl LegacyTrixel {
+    /// The discriminator of a `Trixel` account, which legacy trixels were created with
+    pub const DISCRIMINATOR: [u8; 8] = [13, 30, 39, 148, 167, 117, 147, 155];
 }
 
 impl Trixel {
 
     pub fn bytes() -> usize {
-        8 + std::mem::size_of::<Trixel>() + 50 // arbitrary data
+        8 + std::mem::size_of::<Trixel>()
+    }
+
+    pub fn data(&self) -> Result<TrixelData> {
+        self.data.unpack()
+    }
+
+    pub fn set_data(&mut self, data: TrixelData) {
+        self.data = PackedTrixelData::pack(&data);
+    }
+
+    /// Applies a roll-up from a descendant's write to the trixel's data
+    pub fn roll_up(&mut self, roll_up: &RollUp) -> Result<()> {
+        let mut data = self.data()?;
+        data.roll_up(roll_up)?;
+        self.set_data(data);
+        Ok(())
+    }
+
+    pub fn owner(&self) -> Option<Pubkey> {
+        (self.owner != Pubkey::default()).then_some(self.owner)
+    }
+
+    pub fn set_owner(&mut self, owner: Option<Pubkey>) {
+        self.owner = owner.unwrap_or_default();
+    }
+
+    /// Whether any descendant of the trixel has been created
+    pub fn has_children(&self) -> bool {
+        self.child_hashes != [[0; 32]; 4]
     }
 
-    pub fn new()-> Self {
-        Self::default()
+    pub fn is_frozen(&self) -> bool {
+        self.frozen != 0
+    }
+
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen.into();
+    }
+
+    /// Copies a trixel read in the legacy layout
+    ///
+    /// Ancestors only count owned descendants claimed after they became zero-copy,
+    /// so an owned legacy trixel doesn't keep its ancestors from being claimed
+    pub fn migrate(&mut self, legacy: &LegacyTrixel, bump: u8) {
+        self.world = legacy.world;
+        self.bump = bump;
+        self.hash_version = 0;  // Legacy trixels predate domain-separated hashes
+        self.id = legacy.id;
+        self.resolution = legacy.resolution;
+        self.updates = legacy.updates;
+        self.last_update = legacy.last_update;
+        self.hash = legacy.hash;
+        self.child_hashes = legacy.child_hashes;
+        self.set_data(legacy.data);
+        self.set_frozen(legacy.frozen);
+        self.set_owner(legacy.owner);
     }
 
     pub fn init(
@@ -32,32 +115,48 @@ impl Trixel {
         world: Pubkey,
         id: u64,
         resolution: u8,
+        bump: u8,
+        hash_version: u8,
         world_data_type: TrixelDataType
     ) -> Result<()> {
         self.world = world;
         self.id = id;
         self.resolution = resolution;
+        self.bump = bump;
+        self.hash_version = hash_version;
         self.child_hashes = [[0; 32]; 4];
         self.last_update = Clock::get()?.unix_timestamp;
         self.updates = 0;
-        self.data = match world_data_type {
+        self.set_data(match world_data_type {
             TrixelDataType::Count => TrixelData::Count { count: 0 },
             TrixelDataType::AggregateOverwrite => TrixelData::AggregateOverwrite { metric: 0 },
             TrixelDataType::AggregateAccumulate => TrixelData::AggregateAccumulate { metric: 0 },
             TrixelDataType::MeanOverwrite => TrixelData::MeanOverwrite { numerator: 0, denominator: 0 },
             TrixelDataType::MeanAccumulate => TrixelData::MeanAccumulate { numerator: 0, denominator: 0 },
-        };
+        });
         self.hash = self.compute_hash()?;
         Ok(())
     }
 
     /// Computes the hash of the trixel's data and child hashes
+    ///
+    /// From version 1 it is prefixed with a domain separator and commits to the
+    /// trixel's world, id, resolution and data type, so two trixels holding the
+    /// same data never share a hash
     pub fn compute_hash(&self) -> Result<[u8; 32]> {
         // Create a buffer to hold the data and child hashes
         let mut data_buffer = Vec::with_capacity(std::mem::size_of::<u64>() + 4 * 32); // Adjusted capacity estimation
+
+        if self.hash_version >= 1 {
+            data_buffer.extend_from_slice(TRIXEL_HASH_DOMAIN);
+            data_buffer.extend_from_slice(self.world.as_ref());
+            data_buffer.extend_from_slice(&self.id.to_le_bytes());
+            data_buffer.push(self.resolution);
+            data_buffer.push(self.data()?.to_data_type() as u8);
+        }
         
         // Add the data value
-        data_buffer.extend_from_slice(&self.data.try_to_vec()?); // Changed to try_to_vec()
+        data_buffer.extend_from_slice(&self.data()?.try_to_vec()?); // Changed to try_to_vec()
         
         // Add all child hashes
         for hash in self.child_hashes.iter() {
@@ -108,7 +207,8 @@ impl Trixel {
     /// * `Result<[u8; 32]>` - The newly computed trixel hash
     pub fn update_data(&mut self, new_data: TrixelData) -> Result<[u8; 32]> {
         // Update data based on the type (accumulate or overwrite)
-        match (&mut self.data, new_data) {
+        let mut data = self.data()?;
+        match (&mut data, new_data) {
             // Count accumulation
             (TrixelData::Count { count }, TrixelData::Count { count: new_count }) => {
                 *count = count.checked_add(new_count).unwrap_or(u32::MAX);
@@ -124,12 +224,91 @@ impl Trixel {
                 *denominator = denominator.checked_add(new_denominator).unwrap_or(u64::MAX);
             },
             // For overwrite types, simply replace the data
-            _ => self.data = new_data,
+            _ => data = new_data,
         }
+        self.set_data(data);
         
         self.hash = self.compute_hash()?;
         self.last_update = Clock::get()?.unix_timestamp;
         self.updates = self.updates.checked_add(1).unwrap();
         Ok(self.hash)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_legacy_trixel() {
+        let legacy = LegacyTrixel {
+            world: Pubkey::new_unique(),
+            id: 2135,
+            resolution: 3,
+            updates: 7,
+            last_update: 1_700_000_000,
+            hash: [0; 32],
+            child_hashes: [[1; 32], [2; 32], [3; 32], [4; 32]],
+            data: TrixelData::MeanAccumulate { numerator: 42, denominator: 1 },
+            frozen: true,
+            owner: None,
+        };
+
+        let mut trixel: Trixel = bytemuck::Zeroable::zeroed();
+        trixel.migrate(&legacy, 254);
+        assert_eq!(trixel.bump, 254);
+        assert_eq!(trixel.data().unwrap(), legacy.data);
+        assert_eq!(trixel.owner(), None);
+        assert!(trixel.is_frozen());
+
+        // The hash still covers the Borsh encoding of the data, so it carries over
+        let mut legacy_preimage = legacy.data.try_to_vec().unwrap();
+        for hash in legacy.child_hashes.iter() {
+            legacy_preimage.extend_from_slice(hash);
+        }
+        let legacy_hash = anchor_lang::solana_program::hash::hash(&legacy_preimage).to_bytes();
+        assert_eq!(trixel.compute_hash().unwrap(), legacy_hash);
+
+        // Every data type survives packing
+        for data in [
+            TrixelData::Count { count: u32::MAX },
+            TrixelData::AggregateOverwrite { metric: 5 },
+            TrixelData::AggregateAccumulate { metric: u64::MAX },
+            TrixelData::MeanOverwrite { numerator: 9, denominator: 0 },
+        ] {
+            trixel.set_data(data);
+            assert_eq!(trixel.data().unwrap(), data);
+        }
+
+        assert_eq!(Trixel::bytes(), 8 + 280);
+
+        // Legacy and migrated trixels can't be mistaken for each other
+        let discriminator = |name: &str| anchor_lang::solana_program::hash::hash(name.as_bytes()).to_bytes()[..8].to_vec();
+        assert_eq!(LegacyTrixel::DISCRIMINATOR.to_vec(), discriminator("account:Trixel"));
+        assert_eq!(<Trixel as anchor_lang::Discriminator>::DISCRIMINATOR.to_vec(), discriminator("account:TrixelV2"));
+    }
+
+    #[test]
+    fn test_hash_commits_to_position() {
+        let mut trixel: Trixel = bytemuck::Zeroable::zeroed();
+        trixel.world = Pubkey::new_unique();
+        trixel.id = 115;
+        trixel.resolution = 2;
+        trixel.set_data(TrixelData::Count { count: 3 });
+
+        // Version 0 only covers the data and child hashes
+        let legacy_hash = trixel.compute_hash().unwrap();
+        let mut other = trixel;
+        other.id = 215;
+        assert_eq!(other.compute_hash().unwrap(), legacy_hash);
+
+        trixel.hash_version = 1;
+        other.hash_version = 1;
+        let hash = trixel.compute_hash().unwrap();
+        assert_ne!(hash, legacy_hash);
+        assert_ne!(other.compute_hash().unwrap(), hash);
+        other.id = trixel.id;
+        other.world = Pubkey::new_unique();
+        assert_ne!(other.compute_hash().unwrap(), hash);
+    }
+}
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
 
 /// Represents different types of data that can be stored in a trixel
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
@@ -10,6 +11,13 @@ pub enum TrixelDataType {
     MeanAccumulate,
 }
 
+impl TrixelDataType {
+    /// Whether the type averages its values rather than summing them
+    pub fn is_mean(&self) -> bool {
+        matches!(self, TrixelDataType::MeanOverwrite | TrixelDataType::MeanAccumulate)
+    }
+}
+
 /// Represents different types of data that can be stored in a trixel
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
 pub enum TrixelData {
@@ -44,9 +52,219 @@ impl TrixelData {
     }
 }
 
+/// How a leaf's value is shared among its children when a world's canonical
+/// resolution is refined
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SplitRule {
+    Divide,  // Each child gets an even share, the remainder going to the first children. Means share out their samples
+    Replicate,  // Each child gets the whole value, which keeps means unchanged. Means only, as it would inflate sums
+}
+
+/// The change a write to a canonical trixel makes to each of its ancestors and the world
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct RollUp {
+    pub count: i64,
+    pub metric: i64,  // Also the numerator of mean types
+    pub denominator: i64,
+}
+
+impl RollUp {
+    pub fn negate(&self) -> Self {
+        Self {
+            count: -self.count,
+            metric: -self.metric,
+            denominator: -self.denominator,
+        }
+    }
+}
+
+impl TrixelData {
+    /// Applies a roll-up from a descendant's write to this data
+    pub fn roll_up(&mut self, roll_up: &RollUp) -> Result<()> {
+        match self {
+            TrixelData::Count { count } => {
+                *count = i64::from(*count).checked_add(roll_up.count)
+                    .and_then(|count| u32::try_from(count).ok())
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+            TrixelData::AggregateOverwrite { metric } | TrixelData::AggregateAccumulate { metric } => {
+                *metric = metric.checked_add_signed(roll_up.metric).ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+            TrixelData::MeanOverwrite { numerator, denominator } | TrixelData::MeanAccumulate { numerator, denominator } => {
+                *numerator = numerator.checked_add_signed(roll_up.metric).ok_or(ErrorCode::ArithmeticOverflow)?;
+                *denominator = denominator.checked_add_signed(roll_up.denominator).ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the data of a disjoint trixel of the same type, as the parent of both would hold it
+    pub fn add(&mut self, other: &TrixelData) -> Result<()> {
+        match (self, other) {
+            (TrixelData::Count { count }, TrixelData::Count { count: other }) => {
+                *count = count.checked_add(*other).ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+            (TrixelData::AggregateOverwrite { metric }, TrixelData::AggregateOverwrite { metric: other })
+            | (TrixelData::AggregateAccumulate { metric }, TrixelData::AggregateAccumulate { metric: other }) => {
+                *metric = metric.checked_add(*other).ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+            (TrixelData::MeanOverwrite { numerator, denominator }, TrixelData::MeanOverwrite { numerator: other_numerator, denominator: other_denominator })
+            | (TrixelData::MeanAccumulate { numerator, denominator }, TrixelData::MeanAccumulate { numerator: other_numerator, denominator: other_denominator }) => {
+                *numerator = numerator.checked_add(*other_numerator).ok_or(ErrorCode::ArithmeticOverflow)?;
+                *denominator = denominator.checked_add(*other_denominator).ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+            _ => return Err(ErrorCode::AccountMismatch.into()),
+        }
+        Ok(())
+    }
+    /// The data of a trixel with nothing written to it
+    pub fn zero(data_type: TrixelDataType) -> Self {
+        match data_type {
+            TrixelDataType::Count => TrixelData::Count { count: 0 },
+            TrixelDataType::AggregateOverwrite => TrixelData::AggregateOverwrite { metric: 0 },
+            TrixelDataType::AggregateAccumulate => TrixelData::AggregateAccumulate { metric: 0 },
+            TrixelDataType::MeanOverwrite => TrixelData::MeanOverwrite { numerator: 0, denominator: 0 },
+            TrixelDataType::MeanAccumulate => TrixelData::MeanAccumulate { numerator: 0, denominator: 0 },
+        }
+    }
+
+    /// The share of a leaf's data child `index` of `parts` gets under `rule`
+    ///
+    /// Dividing a mean shares out its denominator, and gives each child the part of
+    /// the numerator its samples carry, so the children sum back to the leaf
+    pub fn split(&self, rule: SplitRule, index: u64, parts: u64) -> Self {
+        let share = |value: u64| match rule {
+            SplitRule::Divide => value / parts + u64::from(index < value % parts),
+            SplitRule::Replicate => value,
+        };
+        let mean_share = |numerator: u64, denominator: u64| {
+            if rule == SplitRule::Replicate || denominator == 0 {
+                return (share(numerator), share(denominator));
+            }
+            // The samples given to the children before this one, and up to it
+            let before = index * (denominator / parts) + index.min(denominator % parts);
+            let through = before + share(denominator);
+            let numerator_at = |samples: u64| (u128::from(numerator) * u128::from(samples) / u128::from(denominator)) as u64;
+            (numerator_at(through) - numerator_at(before), through - before)
+        };
+        match *self {
+            TrixelData::Count { count } => TrixelData::Count {
+                count: share(count.into()) as u32,
+            },
+            TrixelData::AggregateOverwrite { metric } => TrixelData::AggregateOverwrite { metric: share(metric) },
+            TrixelData::AggregateAccumulate { metric } => TrixelData::AggregateAccumulate { metric: share(metric) },
+            TrixelData::MeanOverwrite { numerator, denominator } => {
+                let (numerator, denominator) = mean_share(numerator, denominator);
+                TrixelData::MeanOverwrite { numerator, denominator }
+            }
+            TrixelData::MeanAccumulate { numerator, denominator } => {
+                let (numerator, denominator) = mean_share(numerator, denominator);
+                TrixelData::MeanAccumulate { numerator, denominator }
+            }
+        }
+    }
+
+    /// A trixel's data as the leaf it becomes when its descendants are merged into
+    /// it. Sums carry over, means become a single sample so later writes roll up
+    /// the way they do for any other leaf
+    pub fn merged(&self) -> Self {
+        match *self {
+            TrixelData::MeanOverwrite { numerator, denominator } if denominator > 1 => TrixelData::MeanOverwrite {
+                numerator: numerator / denominator,
+                denominator: 1,
+            },
+            TrixelData::MeanAccumulate { numerator, denominator } if denominator > 1 => TrixelData::MeanAccumulate {
+                numerator: numerator / denominator,
+                denominator: 1,
+            },
+            data => data,
+        }
+    }
+}
+
+/// TrixelData as a tagged, fixed width union, for zero-copy accounts. `tag` is the
+/// TrixelDataType and `values` the variant's fields in declaration order
+#[zero_copy]
+#[derive(Default, Debug, PartialEq)]
+pub struct PackedTrixelData {
+    pub values: [u64; 2],
+    pub tag: u8,
+    pub _padding: [u8; 7],
+}
+
+impl PackedTrixelData {
+    pub fn pack(data: &TrixelData) -> Self {
+        let values = match *data {
+            TrixelData::Count { count } => [count.into(), 0],
+            TrixelData::AggregateOverwrite { metric } | TrixelData::AggregateAccumulate { metric } => [metric, 0],
+            TrixelData::MeanOverwrite { numerator, denominator } | TrixelData::MeanAccumulate { numerator, denominator } => [numerator, denominator],
+        };
+        Self { values, tag: data.to_data_type() as u8, _padding: [0; 7] }
+    }
+
+    pub fn unpack(&self) -> Result<TrixelData> {
+        let [first, second] = self.values;
+        Ok(match self.tag {
+            0 => TrixelData::Count { count: u32::try_from(first).map_err(|_| ErrorCode::InvalidAccount)? },
+            1 => TrixelData::AggregateOverwrite { metric: first },
+            2 => TrixelData::AggregateAccumulate { metric: first },
+            3 => TrixelData::MeanOverwrite { numerator: first, denominator: second },
+            4 => TrixelData::MeanAccumulate { numerator: first, denominator: second },
+            _ => return Err(ErrorCode::InvalidAccount.into()),
+        })
+    }
+}
+
 impl Default for TrixelData {
     fn default() -> Self {
         TrixelData::Count { count: 0 }
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_merge() {
+        let sum = TrixelData::AggregateAccumulate { metric: 10 };
+        let shares: Vec<TrixelData> = (0..4).map(|i| sum.split(SplitRule::Divide, i, 4)).collect();
+        assert_eq!(shares[0], TrixelData::AggregateAccumulate { metric: 3 });
+        assert_eq!(shares[3], TrixelData::AggregateAccumulate { metric: 2 });
+        let mut total = TrixelData::zero(TrixelDataType::AggregateAccumulate);
+        for share in shares.iter() {
+            total.add(share).unwrap();
+        }
+        assert_eq!(total, sum);
+
+        // Replicating a mean keeps it, and merging the children gives it back
+        let mean = TrixelData::MeanOverwrite { numerator: 7, denominator: 1 };
+        let mut total = TrixelData::zero(TrixelDataType::MeanOverwrite);
+        for i in 0..4 {
+            total.add(&mean.split(SplitRule::Replicate, i, 4)).unwrap();
+        }
+        assert_eq!(total, TrixelData::MeanOverwrite { numerator: 28, denominator: 4 });
+        assert_eq!(total.merged(), mean);
+
+        // Dividing a mean shares out its samples, so the children sum back to it
+        for mean in [
+            TrixelData::MeanAccumulate { numerator: 10, denominator: 1 },
+            TrixelData::MeanAccumulate { numerator: 23, denominator: 6 },
+            TrixelData::MeanAccumulate { numerator: 7, denominator: 0 },
+        ] {
+            let shares: Vec<TrixelData> = (0..4).map(|i| mean.split(SplitRule::Divide, i, 4)).collect();
+            let mut total = TrixelData::zero(TrixelDataType::MeanAccumulate);
+            for share in shares.iter() {
+                total.add(share).unwrap();
+            }
+            assert_eq!(total, mean);
+        }
+        // A single sample goes whole to the first child
+        let shares: Vec<TrixelData> = (0..4)
+            .map(|i| TrixelData::MeanOverwrite { numerator: 10, denominator: 1 }.split(SplitRule::Divide, i, 4))
+            .collect();
+        assert_eq!(shares[0], TrixelData::MeanOverwrite { numerator: 10, denominator: 1 });
+        assert_eq!(shares[1], TrixelData::MeanOverwrite { numerator: 0, denominator: 0 });
+    }
+}
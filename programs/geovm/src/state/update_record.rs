@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use super::trixel::Trixel;
+use super::trixel_data::{RollUp, TrixelData};
+
+/// Where a recorded write is in its challenge window
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum DisputeStatus {
+    #[default]
+    Open,
+    Challenged,
+    Settled,
+}
+
+/// A write to a canonical trixel in a world with a challenge window, seeded by
+/// `[b"update", trixel, update_index]`
+///
+/// Keeps what is needed to revert the write, and escrows the updater's bond
+/// until the write can no longer be challenged
+#[derive(Default)]
+#[account]
+pub struct UpdateRecord {
+    pub world: Pubkey,
+    pub trixel: Pubkey,
+    pub trixel_id: u64,
+    pub update_index: u64,
+    pub updater: Pubkey,
+    pub value: i32,
+    pub previous_data: TrixelData,
+    pub roll_up: RollUp,
+    pub bond: u64,
+    pub recorded_at: i64,
+    pub status: DisputeStatus,
+}
+
+impl UpdateRecord {
+
+    pub fn bytes() -> usize {
+        8 + std::mem::size_of::<UpdateRecord>()
+    }
+
+    /// Records the write that has just been applied to `trixel`
+    pub fn init(
+        &mut self,
+        trixel_account: &AccountLoader<Trixel>,
+        updater: Pubkey,
+        value: i32,
+        previous_data: TrixelData,
+        roll_up: RollUp,
+        bond: u64
+    ) -> Result<()> {
+        let trixel = trixel_account.load()?;
+        self.world = trixel.world;
+        self.trixel = trixel_account.key();
+        self.trixel_id = trixel.id;
+        self.update_index = trixel.updates.saturating_sub(1);
+        self.updater = updater;
+        self.value = value;
+        self.previous_data = previous_data;
+        self.roll_up = roll_up;
+        self.bond = bond;
+        self.recorded_at = Clock::get()?.unix_timestamp;
+        self.status = DisputeStatus::Open;
+        Ok(())
+    }
+}
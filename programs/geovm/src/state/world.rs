@@ -1,14 +1,64 @@
 use crate::errors::ErrorCode;
 use anchor_lang::prelude::*;
 
-use super::trixel_data::{TrixelDataType, TrixelData};
+use super::trixel_data::{SplitRule, TrixelDataType, TrixelData};
+use super::fee_schedule::FeeSchedule;
+use crate::helpers::htm::{get_child_index, get_trixel_ancestors, resolution_from_trixel_id};
 
 const ABSOLUTE_MAX_RESOLUTION: u8 = 10;
+/// The hash scheme of new worlds and their trixels. Version 0 hashes only data
+/// and child hashes, version 1 adds a domain prefix and commits to the node's
+/// position and world, see compute_root_hash and Trixel::compute_hash
+pub const HASH_VERSION: u8 = 1;
+const WORLD_HASH_DOMAIN: &[u8] = b"geovm:world:v1";
+pub const MAX_WORLD_BOUNDS: usize = 8;
 
 
+/// A change of a world's canonical resolution by one level, in progress
+///
+/// Refining splits each written leaf into its children, coarsening merges the
+/// trixels at the target resolution into leaves. Writes are rejected until it
+/// finishes, see finish_resolution_change
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ResolutionChange {
+    pub target_resolution: u8,
+    pub split_rule: SplitRule,
+}
+
 #[derive(Default)]
 #[account]
 pub struct World {
+    pub authority: Pubkey,
+    pub name: [u8; 32],
+    pub canonical_resolution: u8,
+    pub permissioned_updates: bool,
+    pub updates: u64,
+    pub root_hash: [u8;32],
+    pub child_hashes: [[u8;32]; 8],
+    pub data: TrixelData,
+    pub reveal_window: i64,
+    pub fee_schedule: Option<FeeSchedule>,
+    pub min_update_interval: i64,
+    pub max_updates_per_epoch: u32,
+    pub paused: bool,
+    pub claims_enabled: bool,
+    pub challenge_window: i64,
+    pub dispute_bond: u64,
+    pub arbiter: Option<Pubkey>,
+    pub min_stake: u64,
+    pub stake_mint: Option<Pubkey>,
+    pub unbonding_period: i64,
+    pub bounds: [u64; MAX_WORLD_BOUNDS],  // Root trixels of the region the world covers, 0 for unused slots, all unused for the whole sphere
+    pub rooted_at_bounds: bool,  // The hash tree stops at the bounds, whose hashes take the place of the 8 faces in child_hashes
+    pub variable_resolution: bool,  // Accepts writes at any resolution up to canonical_resolution, see check_resolution
+    pub resolution_change: Option<ResolutionChange>,
+    pub hash_version: u8  // 0 for worlds created before hashes were domain separated
+}
+
+/// The Borsh layout worlds had before the fields above were added, read by
+/// migrate_world. Those worlds were allocated bytes() and are too small for World
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyWorld {
     pub authority: Pubkey,
     pub name: [u8; 32],
     pub canonical_resolution: u8,
@@ -19,6 +69,13 @@ pub struct World {
     pub data: TrixelData
 }
 
+impl LegacyWorld {
+
+    pub fn bytes() -> usize {
+        8 + std::mem::size_of::<LegacyWorld>() + 50
+    }
+}
+
 impl World {
 
     pub fn bytes() -> usize {
@@ -27,6 +84,7 @@ impl World {
 
     pub fn init(
         &mut self,
+        key: &Pubkey,
         authority: Pubkey,
         name: [u8;32],
         canonical_resolution: u8,
@@ -47,8 +105,23 @@ impl World {
         self.canonical_resolution = canonical_resolution;
         self.permissioned_updates = permissioned_updates;
         self.child_hashes = [[0; 32]; 8];
-        self.root_hash = self.compute_root_hash().unwrap();
         self.updates = 0;
+        self.reveal_window = 0;
+        self.fee_schedule = None;
+        self.min_update_interval = 0;
+        self.max_updates_per_epoch = 0;
+        self.paused = false;
+        self.claims_enabled = false;
+        self.challenge_window = 0;
+        self.dispute_bond = 0;
+        self.arbiter = None;
+        self.min_stake = 0;
+        self.stake_mint = None;
+        self.unbonding_period = 0;
+        self.bounds = [0; MAX_WORLD_BOUNDS];
+        self.rooted_at_bounds = false;
+        self.variable_resolution = false;
+        self.resolution_change = None;
         
         // Initialize the appropriate TrixelData based on the data_type
         self.data = match data_type {
@@ -58,14 +131,52 @@ impl World {
             TrixelDataType::MeanOverwrite => TrixelData::MeanOverwrite { numerator: 0, denominator: 0 },
             TrixelDataType::MeanAccumulate => TrixelData::MeanAccumulate { numerator: 0, denominator: 0 },
         };
+        self.hash_version = HASH_VERSION;
+        self.root_hash = self.compute_root_hash(key)?;
         
         Ok(())
     }
 
+    /// Copies a world read in the legacy layout, leaving every later setting
+    /// at its default, so the world behaves as it did before the upgrade
+    ///
+    /// It keeps hash version 0, so its stored root hash and the hashes of its
+    /// trixels stay valid and proofs made before the upgrade still verify
+    pub fn migrate(&mut self, legacy: &LegacyWorld) {
+        *self = World {
+            hash_version: 0,
+            authority: legacy.authority,
+            name: legacy.name,
+            canonical_resolution: legacy.canonical_resolution,
+            permissioned_updates: legacy.permissioned_updates,
+            updates: legacy.updates,
+            root_hash: legacy.root_hash,
+            child_hashes: legacy.child_hashes,
+            data: legacy.data,
+            ..Default::default()
+        };
+    }
+
     /// Computes the root hash from the child hashes
-    pub fn compute_root_hash(&self) -> Result<[u8; 32]> {
+    ///
+    /// From version 1 it also commits to the world's key, the configuration
+    /// that gives the tree its meaning (canonical resolution, data type and
+    /// bounds) and its data, so a proof can't be replayed against another world
+    pub fn compute_root_hash(&self, key: &Pubkey) -> Result<[u8; 32]> {
         // Create a buffer to hold the child hashes
         let mut data = Vec::with_capacity(8 * 32);
+
+        if self.hash_version >= 1 {
+            data.extend_from_slice(WORLD_HASH_DOMAIN);
+            data.extend_from_slice(key.as_ref());
+            data.push(self.canonical_resolution);
+            data.push(self.data.to_data_type() as u8);
+            data.push(self.rooted_at_bounds.into());
+            for root in self.bounds.iter() {
+                data.extend_from_slice(&root.to_le_bytes());
+            }
+            data.extend_from_slice(&self.data.try_to_vec()?);
+        }
         
         // Add all child hashes
         for hash in self.child_hashes.iter() {
@@ -77,21 +188,149 @@ impl World {
         Ok(hash.to_bytes())
     }
 
+    /// Recomputes the root hash after a change to what it commits to
+    pub fn refresh_root_hash(&mut self, key: &Pubkey) -> Result<[u8; 32]> {
+        self.root_hash = self.compute_root_hash(key)?;
+        Ok(self.root_hash)
+    }
+
     /// Updates the child hash at the specified index and recomputes the root hash
     /// 
     /// # Arguments
     /// 
+    /// * `key` - The world's own key
     /// * `child_idx` - The index of the child hash to update (0-7)
     /// * `new_hash` - The new hash value to set
     /// 
     /// # Returns
     /// 
     /// * `Result<[u8; 32]>` - The newly computed root hash
-    pub fn update_child_hash(&mut self, child_idx: usize, new_hash: [u8; 32]) -> Result<[u8; 32]> {
+    pub fn update_child_hash(&mut self, key: &Pubkey, child_idx: usize, new_hash: [u8; 32]) -> Result<[u8; 32]> {
         require!(child_idx < 8, ErrorCode::InvalidArgument);
         self.child_hashes[child_idx] = new_hash;
-        self.root_hash = self.compute_root_hash()?;
-        Ok(self.root_hash)
+        self.refresh_root_hash(key)
+    }
+
+    /// Whether no trixel has been created in the world yet
+    pub fn is_empty(&self) -> bool {
+        self.child_hashes == [[0; 32]; 8]
+    }
+
+    /// The root trixels of the world's region, empty when it covers the sphere
+    pub fn bound_roots(&self) -> impl Iterator<Item = u64> + '_ {
+        self.bounds.iter().copied().filter(|root| *root != 0)
+    }
+
+    /// Restricts the world to the region under `roots`, which must not overlap
+    ///
+    /// The hash tree can only be rooted at the bounds, or moved back to the
+    /// faces, before any trixel has been created
+    pub fn set_bounds(&mut self, roots: &[u64], rooted: bool) -> Result<()> {
+        require!(roots.len() <= MAX_WORLD_BOUNDS, ErrorCode::InvalidArgument);
+        require!(!rooted || !roots.is_empty(), ErrorCode::InvalidArgument);
+        require!(
+            self.is_empty() || (!rooted && !self.rooted_at_bounds),
+            ErrorCode::InvalidArgument
+        );
+        for (i, root) in roots.iter().enumerate() {
+            require!(
+                resolution_from_trixel_id(*root)? <= self.canonical_resolution,
+                ErrorCode::InvalidResolution
+            );
+            for other in roots[..i].iter() {
+                require!(
+                    !contains(*root, *other)? && !contains(*other, *root)?,
+                    ErrorCode::InvalidArgument
+                );
+            }
+        }
+
+        self.bounds = [0; MAX_WORLD_BOUNDS];
+        self.bounds[..roots.len()].copy_from_slice(roots);
+        self.rooted_at_bounds = rooted;
+        Ok(())
+    }
+
+    /// The bound root containing trixel `id`, or None if the world covers the
+    /// sphere. Fails with OutOfBounds if the trixel is outside the region
+    pub fn bound_root(&self, id: u64) -> Result<Option<u64>> {
+        let mut roots = self.bound_roots().peekable();
+        if roots.peek().is_none() {
+            return Ok(None);
+        }
+        for root in roots {
+            if contains(root, id)? {
+                return Ok(Some(root));
+            }
+        }
+        Err(ErrorCode::OutOfBounds.into())
+    }
+
+    /// Fails with InvalidResolution unless the world accepts writes at `resolution`
+    ///
+    /// Worlds with variable resolution accept any resolution up to the canonical
+    /// one. Only trixels without descendants can be written, so each leaf of the
+    /// tree holds its own data and every other trixel holds the roll-up of its
+    /// descendants, whatever their resolution
+    pub fn check_resolution(&self, resolution: u8) -> Result<()> {
+        require!(
+            resolution == self.canonical_resolution
+                || (self.variable_resolution && resolution <= self.canonical_resolution),
+            ErrorCode::InvalidResolution
+        );
+        Ok(())
+    }
+
+    /// Starts moving the canonical resolution one level finer or coarser
+    pub fn start_resolution_change(&mut self, change: ResolutionChange) -> Result<()> {
+        require!(self.resolution_change.is_none(), ErrorCode::ResolutionChangeInProgress);
+        require!(
+            change.target_resolution.abs_diff(self.canonical_resolution) == 1
+                && (1..=ABSOLUTE_MAX_RESOLUTION).contains(&change.target_resolution),
+            ErrorCode::InvalidResolution
+        );
+        for root in self.bound_roots() {
+            require!(
+                resolution_from_trixel_id(root)? <= change.target_resolution,
+                ErrorCode::InvalidResolution
+            );
+        }
+        // Replicating a sum would count it once per child
+        require!(
+            change.split_rule == SplitRule::Divide || self.data.to_data_type().is_mean(),
+            ErrorCode::InvalidArgument
+        );
+        self.resolution_change = Some(change);
+        Ok(())
+    }
+
+    /// Fails with OutOfBounds unless trixel `id` is within the world's region
+    pub fn check_bounds(&self, id: u64) -> Result<()> {
+        self.bound_root(id).map(|_| ())
+    }
+
+    /// The ancestors of trixel `id` in the world's hash tree, closest first,
+    /// which end at its bound root rather than a face when rooted at the bounds
+    pub fn trixel_ancestors(&self, id: u64) -> Result<Vec<u64>> {
+        let mut ancestors = get_trixel_ancestors(id)?;
+        if self.rooted_at_bounds {
+            let root = self.bound_root(id)?.ok_or(ErrorCode::OutOfBounds)?;
+            let depth = resolution_from_trixel_id(id)? - resolution_from_trixel_id(root)?;
+            ancestors.truncate(depth.into());
+        }
+        Ok(ancestors)
+    }
+
+    /// The index in child_hashes of the top trixel of the hash tree, `top_id`
+    pub fn root_index(&self, top_id: u64) -> Result<usize> {
+        if self.rooted_at_bounds {
+            return self.bounds
+                .iter()
+                .position(|root| *root == top_id)
+                .ok_or(ErrorCode::OutOfBounds.into());
+        }
+        require!(top_id <= 8, ErrorCode::InvalidTrixelId);
+        Ok(get_child_index(top_id)?.0)
     }
 
     /// Updates the child hash at the specified index, recalculates the root hash,
@@ -99,15 +338,16 @@ impl World {
     /// 
     /// # Arguments
     /// 
+    /// * `key` - The world's own key
     /// * `child_idx` - The index of the child hash to update (0-7)
     /// * `new_hash` - The new hash value to set
     /// 
     /// # Returns
     /// 
     /// * `Result<()>` - Success or error
-    pub fn update_child_hash_and_root(&mut self, child_idx: usize, new_hash: [u8; 32]) -> Result<()> {
+    pub fn update_child_hash_and_root(&mut self, key: &Pubkey, child_idx: usize, new_hash: [u8; 32]) -> Result<()> {
         // Update the child hash and get the new root hash
-        let new_root_hash = self.update_child_hash(child_idx, new_hash)?;
+        let new_root_hash = self.update_child_hash(key, child_idx, new_hash)?;
         
         // Update the stored root hash
         self.updates = self.updates.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
@@ -116,4 +356,149 @@ impl World {
         Ok(())
     }
 
-}
\ No newline at end of file
+}
+
+/// Whether trixel `id` is `root` or one of its descendants. On-chain ids end in
+/// their ancestors' digits, so this compares the trailing digits
+fn contains(root: u64, id: u64) -> Result<bool> {
+    let root_resolution = resolution_from_trixel_id(root)?;
+    if resolution_from_trixel_id(id)? < root_resolution {
+        return Ok(false);
+    }
+    Ok(id % 10u64.pow(u32::from(root_resolution) + 1) == root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn test_migrate_legacy_world() {
+        let legacy = LegacyWorld {
+            authority: Pubkey::new_unique(),
+            name: [7; 32],
+            canonical_resolution: 4,
+            permissioned_updates: true,
+            updates: 12,
+            root_hash: [9; 32],
+            child_hashes: [[1; 32]; 8],
+            data: TrixelData::MeanAccumulate { numerator: 42, denominator: 3 },
+        };
+
+        // A world in an account of the legacy size no longer deserializes
+        assert_eq!(LegacyWorld::bytes(), 8 + 442);
+        let mut account = vec![0; LegacyWorld::bytes()];
+        account[..8].copy_from_slice(World::DISCRIMINATOR);
+        legacy.serialize(&mut &mut account[8..]).unwrap();
+        assert!(World::try_deserialize(&mut account.as_slice()).is_err());
+
+        let mut world = World::default();
+        world.migrate(&LegacyWorld::deserialize(&mut &account[8..]).unwrap());
+        assert_eq!(world.authority, legacy.authority);
+        assert_eq!(world.canonical_resolution, 4);
+        assert!(world.permissioned_updates);
+        assert_eq!(world.updates, 12);
+        assert_eq!(world.child_hashes, legacy.child_hashes);
+        assert_eq!(world.data, legacy.data);
+        assert_eq!(world.fee_schedule, None);
+        assert!(!world.paused);
+
+        // Once grown to the current size it does
+        account.resize(World::bytes(), 0);
+        world.try_serialize(&mut account.as_mut_slice()).unwrap();
+        let migrated = World::try_deserialize(&mut account.as_slice()).unwrap();
+        assert_eq!(migrated.root_hash, legacy.root_hash);
+        assert_eq!(migrated.bounds, [0; MAX_WORLD_BOUNDS]);
+    }
+
+    #[test]
+    fn test_migrated_world_keeps_root_hash() {
+        let child_hashes = [[0; 32], [3; 32], [0; 32], [0; 32], [5; 32], [0; 32], [0; 32], [0; 32]];
+        let legacy = LegacyWorld {
+            authority: Pubkey::new_unique(),
+            name: [0; 32],
+            canonical_resolution: 2,
+            permissioned_updates: false,
+            updates: 2,
+            root_hash: anchor_lang::solana_program::hash::hash(&child_hashes.concat()).to_bytes(),
+            child_hashes,
+            data: TrixelData::Count { count: 2 },
+        };
+
+        let key = Pubkey::new_unique();
+        let mut world = World::default();
+        world.migrate(&legacy);
+        assert_eq!(world.hash_version, 0);
+        assert_eq!(world.refresh_root_hash(&key).unwrap(), legacy.root_hash);
+
+        // Writes after the upgrade keep hashing the legacy way
+        world.update_child_hash_and_root(&key, 1, [4; 32]).unwrap();
+        let mut expected = child_hashes;
+        expected[1] = [4; 32];
+        assert_eq!(world.root_hash, anchor_lang::solana_program::hash::hash(&expected.concat()).to_bytes());
+    }
+
+    #[test]
+    fn test_world_bounds() {
+        let mut world = World { canonical_resolution: 3, ..Default::default() };
+        assert!(world.check_bounds(2315).is_ok());
+
+        // Overlapping roots and roots finer than the canonical resolution are rejected
+        assert!(world.set_bounds(&[15, 315], false).is_err());
+        assert!(world.set_bounds(&[12345], false).is_err());
+
+        world.set_bounds(&[15, 7], true).unwrap();
+        assert!(world.check_bounds(2315).is_ok());
+        assert!(world.check_bounds(7).is_ok());
+        assert!(world.check_bounds(1247).is_ok());
+        assert!(world.check_bounds(2325).is_err());
+        assert!(world.check_bounds(5).is_err());
+
+        // Rooted at the bounds, the tree stops at each trixel's bound root
+        assert_eq!(world.trixel_ancestors(2315).unwrap(), vec![315, 15]);
+        assert_eq!(world.trixel_ancestors(15).unwrap(), Vec::<u64>::new());
+        assert_eq!(world.root_index(15).unwrap(), 0);
+        assert_eq!(world.root_index(7).unwrap(), 1);
+        assert!(world.root_index(5).is_err());
+
+        // Once it has trixels the tree can't be moved
+        world.child_hashes[0] = [1; 32];
+        assert!(world.set_bounds(&[5], true).is_err());
+        assert!(world.set_bounds(&[], false).is_err());
+    }
+
+    #[test]
+    fn test_resolution_change() {
+        let mut world = World { canonical_resolution: 3, ..Default::default() };
+        let change = |target_resolution| ResolutionChange { target_resolution, split_rule: SplitRule::Divide };
+        assert!(world.start_resolution_change(change(5)).is_err());
+        assert!(world.start_resolution_change(change(3)).is_err());
+
+        // The bounds have to stay within the canonical resolution
+        world.set_bounds(&[1315], false).unwrap();
+        assert!(world.start_resolution_change(change(2)).is_err());
+        world.set_bounds(&[15], false).unwrap();
+        world.start_resolution_change(change(2)).unwrap();
+        assert!(world.start_resolution_change(change(4)).is_err());
+
+        // Only means can be replicated
+        let replicate = ResolutionChange { target_resolution: 4, split_rule: SplitRule::Replicate };
+        let mut world = World { canonical_resolution: 3, data: TrixelData::zero(TrixelDataType::AggregateAccumulate), ..Default::default() };
+        assert!(world.start_resolution_change(replicate).is_err());
+        world.data = TrixelData::zero(TrixelDataType::MeanAccumulate);
+        world.start_resolution_change(replicate).unwrap();
+    }
+
+    #[test]
+    fn test_world_resolution() {
+        let mut world = World { canonical_resolution: 3, ..Default::default() };
+        assert!(world.check_resolution(3).is_ok());
+        assert!(world.check_resolution(1).is_err());
+
+        world.variable_resolution = true;
+        assert!(world.check_resolution(0).is_ok());
+        assert!(world.check_resolution(3).is_ok());
+        assert!(world.check_resolution(4).is_err());
+    }
+}
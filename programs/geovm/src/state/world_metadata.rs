@@ -0,0 +1,40 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+const MAX_UNITS_LEN: usize = 16;
+const MAX_DESCRIPTION_LEN: usize = 1024;
+const MAX_LICENSE_LEN: usize = 64;
+const MAX_URI_LEN: usize = 256;
+
+/// Describes a world's values for explorers, seeded by `[b"world_metadata", world]`
+///
+/// Sized to its contents, set_world_metadata reallocates it when they change
+#[derive(Default)]
+#[account]
+pub struct WorldMetadata {
+    pub world: Pubkey,
+    pub units: String,  // e.g. "kWh" or "ppm"
+    pub decimals: u8,  // Values are fixed point, divided by 10^decimals for display
+    pub description: String,
+    pub license: String,  // e.g. an SPDX identifier
+    pub uri: String,  // Points to a JSON schema for the world's data
+}
+
+impl WorldMetadata {
+
+    pub fn bytes(units: &str, description: &str, license: &str, uri: &str) -> usize {
+        8 + 32 + 1 + 4 * 4 + units.len() + description.len() + license.len() + uri.len()
+    }
+
+    /// Checks the strings fit within their limits
+    pub fn validate(units: &str, description: &str, license: &str, uri: &str) -> Result<()> {
+        require!(
+            units.len() <= MAX_UNITS_LEN
+                && description.len() <= MAX_DESCRIPTION_LEN
+                && license.len() <= MAX_LICENSE_LEN
+                && uri.len() <= MAX_URI_LEN,
+            ErrorCode::InvalidArgument
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+// Writes the accounts loaded by the test validator to exercise migrations from
+// the layouts accounts had before them, see [[test.validator.account]] in
+// Anchor.toml. Run with `node tests/fixtures/generate-legacy-fixtures.js`
+//
+// Keys are derived from fixed seeds so the tests can sign as the fixtures'
+// authority with Keypair.fromSeed(legacyFixtureSeed("authority"))
+const { createHash, createPrivateKey, createPublicKey } = require("crypto");
+const { readFileSync, writeFileSync } = require("fs");
+const path = require("path");
+
+// The fixtures are owned by the id the program checks accounts against
+const PROGRAM_ID = readFileSync(path.join(__dirname, "../../programs/geovm/src/lib.rs"), "utf8")
+  .match(/declare_id!\("(\w+)"\)/)[1];
+
+const ALPHABET = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+function toBase58(bytes) {
+  let n = BigInt("0x" + (Buffer.from(bytes).toString("hex") || "0"));
+  let out = "";
+  while (n > 0n) {
+    out = ALPHABET[Number(n % 58n)] + out;
+    n /= 58n;
+  }
+  for (const byte of bytes) {
+    if (byte !== 0) break;
+    out = "1" + out;
+  }
+  return out;
+}
+
+function fromBase58(text) {
+  let n = 0n;
+  for (const char of text) n = n * 58n + BigInt(ALPHABET.indexOf(char));
+  const bytes = Buffer.from(n.toString(16).padStart(64, "0"), "hex");
+  return bytes.subarray(bytes.length - 32);
+}
+
+const sha256 = (...parts) => {
+  const hash = createHash("sha256");
+  parts.forEach(part => hash.update(part));
+  return hash.digest();
+};
+
+// Seeds of the fixtures' keys, mirrored by the tests
+const seed = name => sha256(`geovm:legacy-fixture:${name}`);
+
+function publicKeyFromSeed(seedBytes) {
+  const der = Buffer.concat([Buffer.from("302e020100300506032b657004220420", "hex"), seedBytes]);
+  const spki = createPublicKey(createPrivateKey({ key: der, format: "der", type: "pkcs8" }))
+    .export({ format: "der", type: "spki" });
+  return spki.subarray(spki.length - 32);
+}
+
+// Whether 32 bytes decode to a point of ed25519, which program addresses must not
+function isOnCurve(bytes) {
+  const p = 2n ** 255n - 19n;
+  const pow = (base, exp) => {
+    let result = 1n;
+    base %= p;
+    while (exp > 0n) {
+      if (exp & 1n) result = result * base % p;
+      base = base * base % p;
+      exp >>= 1n;
+    }
+    return result;
+  };
+  const d = (p - 121665n) * pow(121666n, p - 2n) % p;
+  const y = BigInt("0x" + Buffer.from(bytes).reverse().toString("hex")) & (2n ** 255n - 1n);
+  const y2 = y * y % p;
+  const x2 = (y2 - 1n + p) % p * pow(d * y2 % p + 1n, p - 2n) % p;
+  return x2 === 0n || pow(x2, (p - 1n) / 2n) === 1n;
+}
+
+function findProgramAddress(seeds, programId) {
+  for (let bump = 255; bump >= 0; bump--) {
+    const address = sha256(...seeds, Buffer.from([bump]), programId, Buffer.from("ProgramDerivedAddress"));
+    if (!isOnCurve(address)) return [address, bump];
+  }
+  throw new Error("No program address found");
+}
+
+const u64 = value => {
+  const bytes = Buffer.alloc(8);
+  bytes.writeBigUInt64LE(BigInt(value));
+  return bytes;
+};
+
+const discriminator = name => sha256(`account:${name}`).subarray(0, 8);
+
+function writeFixture(name, address, data) {
+  const rentExemptMinimum = (128 + data.length) * 3480 * 2;
+  const fixture = {
+    pubkey: toBase58(address),
+    account: {
+      lamports: rentExemptMinimum,
+      data: [data.toString("base64"), "base64"],
+      owner: PROGRAM_ID,
+      executable: false,
+      rentEpoch: 0,
+      space: data.length,
+    },
+  };
+  writeFileSync(path.join(__dirname, `${name}.json`), JSON.stringify(fixture, null, 2) + "\n");
+  console.log(`${name}: ${fixture.pubkey}`);
+}
+
+const programId = fromBase58(PROGRAM_ID);
+const authority = publicKeyFromSeed(seed("authority"));
+const world = publicKeyFromSeed(seed("world"));
+
+// A world of resolution 1 holding one write of 5 to trixel 12, the first child
+// of face 2, in the Borsh layouts from before the accounts were extended
+const data = Buffer.concat([Buffer.from([1]), u64(5)]);  // TrixelData::AggregateOverwrite { metric: 5 }
+const childHash = sha256(data, Buffer.alloc(4 * 32));
+const faceHash = sha256(data, childHash, Buffer.alloc(3 * 32));
+const worldChildHashes = Buffer.concat([Buffer.alloc(32), faceHash, Buffer.alloc(6 * 32)]);
+
+// World::bytes() was 8 + size_of::<World>() + 50, with size_of::<World>() == 392
+const worldAccount = Buffer.alloc(450);
+Buffer.concat([
+  discriminator("World"),
+  authority,
+  Buffer.from("LegacyWorld".padEnd(32, "\0")),
+  Buffer.from([1, 0]),  // canonical_resolution, permissioned_updates
+  u64(1),  // updates
+  sha256(worldChildHashes),  // root_hash
+  worldChildHashes,
+  data,
+]).copy(worldAccount);
+writeFixture("legacy-world", world, worldAccount);
+
+// Trixel::bytes() was 8 + size_of::<Trixel>() + 50, with size_of::<Trixel>() == 248
+function legacyTrixel(id, resolution, hash, childHashes) {
+  const account = Buffer.alloc(306);
+  Buffer.concat([
+    discriminator("Trixel"),
+    world,
+    u64(id),
+    Buffer.from([resolution]),
+    u64(1),  // updates
+    u64(1_700_000_000),  // last_update
+    hash,
+    childHashes,
+    data,
+  ]).copy(account);
+  const [address] = findProgramAddress([Buffer.from("trixel"), world, u64(id)], programId);
+  writeFixture(`legacy-trixel-${id}`, address, account);
+}
+legacyTrixel(2, 0, faceHash, Buffer.concat([childHash, Buffer.alloc(3 * 32)]));
+legacyTrixel(12, 1, childHash, Buffer.alloc(4 * 32));
@@ -3,11 +3,18 @@ import { Program } from "@coral-xyz/anchor";
 import { Geovm } from "../target/types/geovm";
 import { Keypair, PublicKey, SystemProgram, AccountMeta } from "@solana/web3.js";
 import { expect } from "chai";
+import { createHash, randomBytes } from "crypto";
 import { 
     getTrixelAndAncestorPDAs, 
     getTrixelPDA,
     SphericalCoords,
-    getTrixelAncestors
+    getTrixelAncestors,
+    NO_FEE_ACCOUNTS,
+    NO_REPORTER_ACCOUNTS,
+    NO_WRITE_ACCOUNTS,
+    fetchTrixel,
+    getWorldPDA,
+    getLegacyFixtureKeypair
 } from "./utils";
 
 // Define VALID_IDS_RESOLUTION_5 locally as it's not exported from utils.ts
@@ -90,6 +97,7 @@ describe("geovm", () => {
           world: worldPubkey,
           trixel: trixelPda,
           systemProgram: SystemProgram.programId,
+          fee: NO_FEE_ACCOUNTS,
         })
         .remainingAccounts(
           ancestorPDAs.map(pda => ({
@@ -101,12 +109,12 @@ describe("geovm", () => {
         .rpc();
       
       // Verify the update
-      const trixelAccount = await program.account.trixel.fetch(trixelPda);
+      const trixelAccount = await fetchTrixel(program, trixelPda);
       console.log(trixelAccount);
 
       // Verify ancestor updates
       for (const ancestorPda of ancestorPDAs) {
-        const ancestorAccount = await program.account.trixel.fetch(ancestorPda);
+        const ancestorAccount = await fetchTrixel(program, ancestorPda);
         console.log(ancestorAccount);
       }
 
@@ -127,12 +135,13 @@ describe("geovm", () => {
       // Update the trixel with some data
       const updateValue = 42;
       await program.methods
-        .updateTrixel({ id: new anchor.BN(trixelId), value: updateValue, coords: null })
+        .updateTrixel({ id: new anchor.BN(trixelId), value: updateValue, coords: null, expectedUpdates: null, expectedHash: null })
         .accountsStrict({
           payer: payer.publicKey,
           world: worldPubkey,
           trixel: trixelPda,
           systemProgram: SystemProgram.programId,
+          write: NO_WRITE_ACCOUNTS,
         })
         .remainingAccounts(
           ancestorPDAs.map(pda => ({
@@ -144,14 +153,14 @@ describe("geovm", () => {
         .rpc();
 
       // Verify the update
-      const trixelAccount = await program.account.trixel.fetch(trixelPda);
+      const trixelAccount = await fetchTrixel(program, trixelPda);
 
       console.log(trixelAccount);
       expect(trixelAccount.data.aggregateOverwrite.metric.eq(new anchor.BN(updateValue))).is.true;
 
       // Verify ancestor updates
       for (const ancestorPda of ancestorPDAs) {
-        const ancestorAccount = await program.account.trixel.fetch(ancestorPda);
+        const ancestorAccount = await fetchTrixel(program, ancestorPda);
         console.log(ancestorAccount);
       }
     });
@@ -209,11 +218,12 @@ describe("geovm", () => {
           trixel: targetTrixelPda, // Use the PublicKey
           payer: provider.wallet.publicKey,
           systemProgram: SystemProgram.programId, // Ensure this name matches Rust struct
+          fee: NO_FEE_ACCOUNTS,
         })
         .remainingAccounts(ancestorAccountsMetas)
         .rpc();
 
-      let trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+      let trixelAccount = await fetchTrixel(program, targetTrixelPda);
       expect(trixelAccount.id.eq(targetTrixelIdBN)).is.true;
       expect(trixelAccount.resolution).to.equal(canonicalResolution);
       expect(trixelAccount.data.hasOwnProperty("count")).is.true;
@@ -222,7 +232,7 @@ describe("geovm", () => {
 
       // 3. Update Trixel - First Time
       const updateValue1 = 5; 
-      const updateArgs1 = { id: targetTrixelIdBN, value: updateValue1, coords: null };
+      const updateArgs1 = { id: targetTrixelIdBN, value: updateValue1, coords: null, expectedUpdates: null, expectedHash: null };
 
       await program.methods
         .updateTrixel(updateArgs1)
@@ -231,16 +241,17 @@ describe("geovm", () => {
           trixel: targetTrixelPda, // Use the PublicKey
           payer: provider.wallet.publicKey, 
           systemProgram: SystemProgram.programId, // Ensure this name matches Rust struct
+          write: NO_WRITE_ACCOUNTS,
         })
         .remainingAccounts(ancestorAccountsMetas)
         .rpc();
 
-      trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+      trixelAccount = await fetchTrixel(program, targetTrixelPda);
       // @ts-ignore 
       expect(trixelAccount.data.count.count).to.equal(1);
 
       if (ancestorAccountsMetas.length > 0) {
-        const firstAncestorAccount = await program.account.trixel.fetch(ancestorAccountsMetas[0].pubkey);
+        const firstAncestorAccount = await fetchTrixel(program, ancestorAccountsMetas[0].pubkey);
         // @ts-ignore 
         expect(firstAncestorAccount.data.count.count).to.equal(1);
       }
@@ -251,7 +262,7 @@ describe("geovm", () => {
 
       // 4. Update Trixel - Second Time
       const updateValue2 = 10; 
-      const updateArgs2 = { id: targetTrixelIdBN, value: updateValue2, coords: null };
+      const updateArgs2 = { id: targetTrixelIdBN, value: updateValue2, coords: null, expectedUpdates: null, expectedHash: null };
 
       await program.methods
         .updateTrixel(updateArgs2)
@@ -260,16 +271,17 @@ describe("geovm", () => {
           trixel: targetTrixelPda, // Use the PublicKey
           payer: provider.wallet.publicKey,
           systemProgram: SystemProgram.programId, // Ensure this name matches Rust struct
+          write: NO_WRITE_ACCOUNTS,
         })
         .remainingAccounts(ancestorAccountsMetas)
         .rpc();
 
-      trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+      trixelAccount = await fetchTrixel(program, targetTrixelPda);
       // @ts-ignore 
       expect(trixelAccount.data.count.count).to.equal(2);
 
       if (ancestorAccountsMetas.length > 0) {
-        const firstAncestorAccount = await program.account.trixel.fetch(ancestorAccountsMetas[0].pubkey);
+        const firstAncestorAccount = await fetchTrixel(program, ancestorAccountsMetas[0].pubkey);
         // @ts-ignore 
         expect(firstAncestorAccount.data.count.count).to.equal(2);
       }
@@ -334,32 +346,34 @@ describe("geovm", () => {
         trixel: targetTrixelPda,
         payer: provider.wallet.publicKey,
         systemProgram: SystemProgram.programId,
+        fee: NO_FEE_ACCOUNTS,
       })
       .remainingAccounts(asyncAncestorAccountsMetas)
       .rpc();
     
-    let trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    let trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.aggregateOverwrite.metric.toNumber()).to.equal(0);
 
     // 3. Update Trixel - First Time
     const updateValue1 = 50;
     await program.methods
-      .updateTrixel({ id: targetTrixelIdBN, value: updateValue1, coords: null })
+      .updateTrixel({ id: targetTrixelIdBN, value: updateValue1, coords: null, expectedUpdates: null, expectedHash: null })
       .accountsStrict({
         world: worldKeypair.publicKey,
         trixel: targetTrixelPda,
         payer: provider.wallet.publicKey,
         systemProgram: SystemProgram.programId,
+        write: NO_WRITE_ACCOUNTS,
       })
       .remainingAccounts(asyncAncestorAccountsMetas)
       .rpc();
 
-    trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.aggregateOverwrite.metric.toNumber()).to.equal(updateValue1);
     if (asyncAncestorAccountsMetas.length > 0) {
-      const firstAncestorAccount = await program.account.trixel.fetch(asyncAncestorAccountsMetas[0].pubkey);
+      const firstAncestorAccount = await fetchTrixel(program, asyncAncestorAccountsMetas[0].pubkey);
       // @ts-ignore
       expect(firstAncestorAccount.data.aggregateOverwrite.metric.toNumber()).to.equal(updateValue1);
     }
@@ -370,21 +384,22 @@ describe("geovm", () => {
     // 4. Update Trixel - Second Time (Overwrite)
     const updateValue2 = 30;
     await program.methods
-      .updateTrixel({ id: targetTrixelIdBN, value: updateValue2, coords: null })
+      .updateTrixel({ id: targetTrixelIdBN, value: updateValue2, coords: null, expectedUpdates: null, expectedHash: null })
       .accountsStrict({
         world: worldKeypair.publicKey,
         trixel: targetTrixelPda,
         payer: provider.wallet.publicKey,
         systemProgram: SystemProgram.programId,
+        write: NO_WRITE_ACCOUNTS,
       })
       .remainingAccounts(asyncAncestorAccountsMetas)
       .rpc();
 
-    trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.aggregateOverwrite.metric.toNumber()).to.equal(updateValue2);
     if (asyncAncestorAccountsMetas.length > 0) {
-      const firstAncestorAccount = await program.account.trixel.fetch(asyncAncestorAccountsMetas[0].pubkey);
+      const firstAncestorAccount = await fetchTrixel(program, asyncAncestorAccountsMetas[0].pubkey);
       // @ts-ignore
       expect(firstAncestorAccount.data.aggregateOverwrite.metric.toNumber()).to.equal(updateValue2);
     }
@@ -419,22 +434,22 @@ describe("geovm", () => {
     }
 
     await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
-      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
       .remainingAccounts(ancestorAccountsMetas).rpc();
-    let trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    let trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.aggregateAccumulate.metric.toNumber()).to.equal(0);
 
     const updateValue1 = 70;
-    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue1, coords: null })
-      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
       .remainingAccounts(ancestorAccountsMetas).rpc();
     
-    trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.aggregateAccumulate.metric.toNumber()).to.equal(updateValue1);
     if (ancestorAccountsMetas.length > 0) {
-      const firstAncestorAccount = await program.account.trixel.fetch(ancestorAccountsMetas[0].pubkey);
+      const firstAncestorAccount = await fetchTrixel(program, ancestorAccountsMetas[0].pubkey);
       // @ts-ignore
       expect(firstAncestorAccount.data.aggregateAccumulate.metric.toNumber()).to.equal(updateValue1);
     }
@@ -443,15 +458,15 @@ describe("geovm", () => {
     expect(worldAccount.data.aggregateAccumulate.metric.toNumber()).to.equal(updateValue1);
 
     const updateValue2 = -20;
-    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue2, coords: null })
-      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue2, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
       .remainingAccounts(ancestorAccountsMetas).rpc();
 
-    trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.aggregateAccumulate.metric.toNumber()).to.equal(updateValue1 + updateValue2);
     if (ancestorAccountsMetas.length > 0) {
-      const firstAncestorAccount = await program.account.trixel.fetch(ancestorAccountsMetas[0].pubkey);
+      const firstAncestorAccount = await fetchTrixel(program, ancestorAccountsMetas[0].pubkey);
       // @ts-ignore
       expect(firstAncestorAccount.data.aggregateAccumulate.metric.toNumber()).to.equal(updateValue1 + updateValue2);
     }
@@ -488,9 +503,9 @@ describe("geovm", () => {
     }
 
     await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
-      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
       .remainingAccounts(ancestorAccountsMetas).rpc();
-    let trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    let trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.meanOverwrite.numerator.toNumber()).to.equal(0);
     // @ts-ignore
@@ -498,17 +513,17 @@ describe("geovm", () => {
 
     // Update 1
     const updateValue1 = 100;
-    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue1, coords: null })
-      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
       .remainingAccounts(ancestorAccountsMetas).rpc();
     
-    trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.meanOverwrite.numerator.toNumber()).to.equal(updateValue1);
     // @ts-ignore
     expect(trixelAccount.data.meanOverwrite.denominator.toNumber()).to.equal(1); // Denom becomes 1
     if (ancestorAccountsMetas.length > 0) {
-      const firstAncestorAccount = await program.account.trixel.fetch(ancestorAccountsMetas[0].pubkey);
+      const firstAncestorAccount = await fetchTrixel(program, ancestorAccountsMetas[0].pubkey);
       // @ts-ignore
       expect(firstAncestorAccount.data.meanOverwrite.numerator.toNumber()).to.equal(updateValue1);
       // @ts-ignore
@@ -522,17 +537,17 @@ describe("geovm", () => {
 
     // Update 2 (Overwrite)
     const updateValue2 = 60;
-    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue2, coords: null })
-      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue2, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
       .remainingAccounts(ancestorAccountsMetas).rpc();
     
-    trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.meanOverwrite.numerator.toNumber()).to.equal(updateValue2);
     // @ts-ignore
     expect(trixelAccount.data.meanOverwrite.denominator.toNumber()).to.equal(1); // Stays 1
     if (ancestorAccountsMetas.length > 0) {
-      const firstAncestorAccount = await program.account.trixel.fetch(ancestorAccountsMetas[0].pubkey);
+      const firstAncestorAccount = await fetchTrixel(program, ancestorAccountsMetas[0].pubkey);
       // @ts-ignore
       expect(firstAncestorAccount.data.meanOverwrite.numerator.toNumber()).to.equal(updateValue2); // Old (100) removed, New (60) added
       // @ts-ignore
@@ -573,9 +588,9 @@ describe("geovm", () => {
     }
 
     await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
-      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
       .remainingAccounts(ancestorAccountsMetas).rpc();
-    let trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    let trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.meanAccumulate.numerator.toNumber()).to.equal(0);
     // @ts-ignore
@@ -583,17 +598,17 @@ describe("geovm", () => {
 
     // Update 1
     const updateValue1 = 100;
-    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue1, coords: null })
-      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
       .remainingAccounts(ancestorAccountsMetas).rpc();
     
-    trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.meanAccumulate.numerator.toNumber()).to.equal(updateValue1);
     // @ts-ignore
     expect(trixelAccount.data.meanAccumulate.denominator.toNumber()).to.equal(1);
     if (ancestorAccountsMetas.length > 0) {
-      const firstAncestorAccount = await program.account.trixel.fetch(ancestorAccountsMetas[0].pubkey);
+      const firstAncestorAccount = await fetchTrixel(program, ancestorAccountsMetas[0].pubkey);
       // @ts-ignore
       expect(firstAncestorAccount.data.meanAccumulate.numerator.toNumber()).to.equal(updateValue1);
       // @ts-ignore
@@ -607,17 +622,17 @@ describe("geovm", () => {
 
     // Update 2 (Accumulate)
     const updateValue2 = -30;
-    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue2, coords: null })
-      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: updateValue2, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
       .remainingAccounts(ancestorAccountsMetas).rpc();
     
-    trixelAccount = await program.account.trixel.fetch(targetTrixelPda);
+    trixelAccount = await fetchTrixel(program, targetTrixelPda);
     // @ts-ignore
     expect(trixelAccount.data.meanAccumulate.numerator.toNumber()).to.equal(updateValue1 + updateValue2);
     // @ts-ignore
     expect(trixelAccount.data.meanAccumulate.denominator.toNumber()).to.equal(1); // Stays 1
     if (ancestorAccountsMetas.length > 0) {
-      const firstAncestorAccount = await program.account.trixel.fetch(ancestorAccountsMetas[0].pubkey);
+      const firstAncestorAccount = await fetchTrixel(program, ancestorAccountsMetas[0].pubkey);
       // @ts-ignore
       expect(firstAncestorAccount.data.meanAccumulate.numerator.toNumber()).to.equal(updateValue1 + updateValue2);
       // @ts-ignore
@@ -629,4 +644,2237 @@ describe("geovm", () => {
     // @ts-ignore
     expect(worldAccount.data.meanAccumulate.denominator.toNumber()).to.equal(1);
   });
+
+  it("should only apply revealed updates matching a commitment", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("CommitRevealWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { aggregateOverwrite: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: new anchor.BN(600), minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: null, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorAccountsMetas: AccountMeta[] = getTrixelAncestors(targetTrixelIdBN.toNumber()).map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    // Direct updates are rejected once the world uses commit-reveal
+    try {
+      await program.methods.updateTrixel({ id: targetTrixelIdBN, value: 7, coords: null, expectedUpdates: null, expectedHash: null })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+        .remainingAccounts(ancestorAccountsMetas).rpc();
+      expect.fail("update_trixel should require commit-reveal");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("CommitRevealRequired");
+    }
+
+    // Commit to the update
+    const updateValue = 77;
+    const salt = randomBytes(32);
+    const valueBytes = Buffer.alloc(4);
+    valueBytes.writeInt32LE(updateValue);
+    const hash = createHash("sha256")
+      .update(worldKeypair.publicKey.toBuffer())
+      .update(provider.wallet.publicKey.toBuffer())
+      .update(targetTrixelIdBN.toArrayLike(Buffer, "le", 8))
+      .update(valueBytes)
+      .update(salt)
+      .digest();
+    const [commitmentPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("commitment"), worldKeypair.publicKey.toBuffer(), provider.wallet.publicKey.toBuffer(), hash],
+      program.programId
+    );
+    await program.methods.commitTrixelUpdate({ hash: Array.from(hash) })
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, commitment: commitmentPda, systemProgram: SystemProgram.programId, stake: null })
+      .rpc();
+
+    // A reveal with a different value does not match the commitment
+    try {
+      await program.methods.revealTrixelUpdate({ id: targetTrixelIdBN, value: updateValue + 1, salt: Array.from(salt) })
+        .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: targetTrixelPda, commitment: commitmentPda, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+        .remainingAccounts(ancestorAccountsMetas).rpc();
+      expect.fail("mismatched reveal should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("CommitmentMismatch");
+    }
+
+    await program.methods.revealTrixelUpdate({ id: targetTrixelIdBN, value: updateValue, salt: Array.from(salt) })
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: targetTrixelPda, commitment: commitmentPda, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const trixelAccount = await fetchTrixel(program, targetTrixelPda);
+    // @ts-ignore
+    expect(trixelAccount.data.aggregateOverwrite.metric.toNumber()).to.equal(updateValue);
+    const worldAccount = await program.account.world.fetch(worldKeypair.publicKey);
+    // @ts-ignore
+    expect(worldAccount.data.aggregateOverwrite.metric.toNumber()).to.equal(updateValue);
+    expect(await provider.connection.getAccountInfo(commitmentPda)).to.be.null;
+  });
+
+  it("should not let another signer reuse a revealed commitment", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("CommitReplayWorld".padEnd(32, "\0")));
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution: 5, dataType: { aggregateOverwrite: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: new anchor.BN(600), minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: null, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorAccountsMetas: AccountMeta[] = getTrixelAncestors(targetTrixelIdBN.toNumber()).map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const frontRunner = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(frontRunner.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+
+    // The honest reporter commits, binding the commitment to their key
+    const updateValue = 42;
+    const salt = randomBytes(32);
+    const valueBytes = Buffer.alloc(4);
+    valueBytes.writeInt32LE(updateValue);
+    const hash = createHash("sha256")
+      .update(worldKeypair.publicKey.toBuffer())
+      .update(provider.wallet.publicKey.toBuffer())
+      .update(targetTrixelIdBN.toArrayLike(Buffer, "le", 8))
+      .update(valueBytes)
+      .update(salt)
+      .digest();
+    await program.methods.commitTrixelUpdate({ hash: Array.from(hash) })
+      .accountsStrict({
+        payer: provider.wallet.publicKey,
+        world: worldKeypair.publicKey,
+        commitment: PublicKey.findProgramAddressSync(
+          [Buffer.from("commitment"), worldKeypair.publicKey.toBuffer(), provider.wallet.publicKey.toBuffer(), hash],
+          program.programId
+        )[0],
+        systemProgram: SystemProgram.programId,
+        stake: null
+      })
+      .rpc();
+
+    // Having seen the salt, the front-runner commits the same hash under their own key
+    const [copiedCommitmentPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("commitment"), worldKeypair.publicKey.toBuffer(), frontRunner.publicKey.toBuffer(), hash],
+      program.programId
+    );
+    await program.methods.commitTrixelUpdate({ hash: Array.from(hash) })
+      .accountsStrict({ payer: frontRunner.publicKey, world: worldKeypair.publicKey, commitment: copiedCommitmentPda, systemProgram: SystemProgram.programId, stake: null })
+      .signers([frontRunner])
+      .rpc();
+
+    // But the hash commits to the honest reporter, so their reveal doesn't match
+    try {
+      await program.methods.revealTrixelUpdate({ id: targetTrixelIdBN, value: updateValue, salt: Array.from(salt) })
+        .accountsStrict({ payer: frontRunner.publicKey, world: worldKeypair.publicKey, trixel: targetTrixelPda, commitment: copiedCommitmentPda, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+        .remainingAccounts(ancestorAccountsMetas)
+        .signers([frontRunner])
+        .rpc();
+      expect.fail("a copied commitment should not reveal");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("CommitmentMismatch");
+    }
+    const trixelAccount = await fetchTrixel(program, targetTrixelPda);
+    expect(trixelAccount.updates.toNumber()).to.equal(0);
+  });
+
+  it("should collect lamport update fees into the world treasury", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("FeeWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { aggregateAccumulate: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const [treasuryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), worldKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    const feeAmount = 5000;
+    await program.methods
+      .setFeeSchedule({ feeSchedule: { mint: null, amount: new anchor.BN(feeAmount), scaling: { perResolution: {} } } })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey, treasury: treasuryPda, systemProgram: SystemProgram.programId })
+      .rpc();
+    const lamportFees = { ...NO_FEE_ACCOUNTS, treasury: treasuryPda };
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorAccountsMetas: AccountMeta[] = getTrixelAncestors(targetTrixelIdBN.toNumber()).map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+
+    const treasuryBalanceBefore = await provider.connection.getBalance(treasuryPda);
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: lamportFees })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: 3, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: { ...NO_WRITE_ACCOUNTS, fee: lamportFees } })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    // Each write is charged once per trixel in the roll-up
+    const expectedFees = 2 * feeAmount * (canonicalResolution + 1);
+    const treasuryBalanceAfter = await provider.connection.getBalance(treasuryPda);
+    expect(treasuryBalanceAfter - treasuryBalanceBefore).to.equal(expectedFees);
+
+    // Writes without the treasury are rejected
+    try {
+      await program.methods.updateTrixel({ id: targetTrixelIdBN, value: 3, coords: null, expectedUpdates: null, expectedHash: null })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+        .remainingAccounts(ancestorAccountsMetas).rpc();
+      expect.fail("update_trixel should require the treasury");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("MissingFeeAccount");
+    }
+
+    const destination = anchor.web3.Keypair.generate().publicKey;
+    await program.methods.withdrawTreasury({ amount: new anchor.BN(expectedFees) })
+      .accountsStrict({
+        authority: provider.wallet.publicKey,
+        world: worldKeypair.publicKey,
+        treasury: treasuryPda,
+        destination,
+        mint: null,
+        treasuryTokenAccount: null,
+        destinationTokenAccount: null,
+        tokenProgram: null,
+      })
+      .rpc();
+    expect(await provider.connection.getBalance(destination)).to.equal(expectedFees);
+  });
+
+  it("should pay a first-write bounty to the reporter once", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("BountyWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { aggregateOverwrite: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorAccountsMetas: AccountMeta[] = getTrixelAncestors(targetTrixelIdBN.toNumber()).map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    // The creator funds a lamport bounty for the first reading at the world's resolution
+    const bountyId = new anchor.BN(1);
+    const reward = 1_000_000;
+    const [bountyPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bounty"), worldKeypair.publicKey.toBuffer(), provider.wallet.publicKey.toBuffer(), bountyId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .createBounty({ bountyId, target: { resolution: { resolution: canonicalResolution } }, trigger: { firstWrite: {} }, reward: new anchor.BN(reward) })
+      .accountsStrict({
+        creator: provider.wallet.publicKey,
+        world: worldKeypair.publicKey,
+        bounty: bountyPda,
+        mint: null,
+        bountyTokenAccount: null,
+        tokenProgram: null,
+        associatedTokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods.fundBounty({ amount: new anchor.BN(3 * reward) })
+      .accountsStrict({
+        funder: provider.wallet.publicKey,
+        bounty: bountyPda,
+        mint: null,
+        funderTokenAccount: null,
+        bountyTokenAccount: null,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const [reporterPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reporter"), worldKeypair.publicKey.toBuffer(), provider.wallet.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods.registerReporter()
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, reporter: reporterPda, systemProgram: SystemProgram.programId })
+      .rpc();
+    const bountyAccounts = { ...NO_REPORTER_ACCOUNTS, record: reporterPda, bounty: bountyPda };
+
+    const bountyBalanceBefore = await provider.connection.getBalance(bountyPda);
+    for (const value of [5, 6]) {
+      await program.methods.updateTrixel({ id: targetTrixelIdBN, value, coords: null, expectedUpdates: null, expectedHash: null })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: { ...NO_WRITE_ACCOUNTS, reporter: bountyAccounts } })
+        .remainingAccounts(ancestorAccountsMetas).rpc();
+    }
+
+    // Only the first write paid out
+    const bountyBalanceAfter = await provider.connection.getBalance(bountyPda);
+    expect(bountyBalanceBefore - bountyBalanceAfter).to.equal(reward);
+    const reporterAccount = await program.account.reporter.fetch(reporterPda);
+    expect(reporterAccount.bountyPayouts.toNumber()).to.equal(1);
+    const bountyAccount = await program.account.bounty.fetch(bountyPda);
+    expect(bountyAccount.payouts.toNumber()).to.equal(1);
+  });
+
+  it("should enforce per-trixel cooldowns and per-updater epoch limits", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("RateLimitWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { count: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: new anchor.BN(3600), maxUpdatesPerEpoch: 2, paused: null, claimsEnabled: null, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const [reporterPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reporter"), worldKeypair.publicKey.toBuffer(), provider.wallet.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods.registerReporter()
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, reporter: reporterPda, systemProgram: SystemProgram.programId })
+      .rpc();
+    const reporterAccounts = { ...NO_REPORTER_ACCOUNTS, record: reporterPda };
+
+    const writeTo = async (trixelId: number) => {
+      const [trixelPda] = getTrixelPDA(worldKeypair.publicKey, trixelId, program.programId);
+      const ancestorAccountsMetas: AccountMeta[] = getTrixelAncestors(trixelId).map(id => ({
+        pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+        isSigner: false,
+        isWritable: true,
+      }));
+      await program.methods.updateTrixel({ id: new anchor.BN(trixelId), value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: { ...NO_WRITE_ACCOUNTS, reporter: reporterAccounts } })
+        .remainingAccounts(ancestorAccountsMetas).rpc();
+    };
+
+    const trixelIds = [VALID_IDS_RESOLUTION_5[0], 211112, 211113];
+    for (const trixelId of trixelIds) {
+      const [trixelPda] = getTrixelPDA(worldKeypair.publicKey, trixelId, program.programId);
+      await program.methods.createTrixelAndAncestors({ id: new anchor.BN(trixelId) })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+        .remainingAccounts(getTrixelAncestors(trixelId).map(id => ({
+          pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+          isSigner: false,
+          isWritable: true,
+        }))).rpc();
+    }
+
+    await writeTo(trixelIds[0]);
+
+    // A second write to the same trixel inside the cooldown is rejected
+    try {
+      await writeTo(trixelIds[0]);
+      expect.fail("write inside the cooldown should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("UpdateTooFrequent");
+    }
+
+    // The updater may write twice per epoch in total
+    await writeTo(trixelIds[1]);
+    try {
+      await writeTo(trixelIds[2]);
+      expect.fail("third write in the epoch should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("RateLimitExceeded");
+    }
+  });
+
+  it("should reject writes to paused worlds and frozen subtrees", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("FreezeWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { count: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorIds = getTrixelAncestors(targetTrixelIdBN.toNumber());
+    const ancestorAccountsMetas: AccountMeta[] = ancestorIds.map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const update = () => program.methods.updateTrixel({ id: targetTrixelIdBN, value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    // Freezing the root trixel freezes everything beneath it
+    const rootId = new anchor.BN(ancestorIds[ancestorIds.length - 1]);
+    const setRootFrozen = (frozen: boolean) => program.methods.setTrixelFrozen({ id: rootId, frozen })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: getTrixelPDA(worldKeypair.publicKey, rootId.toNumber(), program.programId)[0] })
+      .rpc();
+    await setRootFrozen(true);
+    try {
+      await update();
+      expect.fail("write beneath a frozen trixel should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("TrixelFrozen");
+    }
+    await setRootFrozen(false);
+    await update();
+
+    const setPaused = (paused: boolean) => program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused, claimsEnabled: null, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+    await setPaused(true);
+    try {
+      await update();
+      expect.fail("write to a paused world should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("WorldPaused");
+    }
+    await setPaused(false);
+    await update();
+
+    const trixelAccount = await fetchTrixel(program, targetTrixelPda);
+    // @ts-ignore
+    expect(trixelAccount.data.count.count).to.equal(2);
+  });
+
+  it("should restrict writes beneath a claimed trixel to its owner", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("ClaimWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { count: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: true, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorIds = getTrixelAncestors(targetTrixelIdBN.toNumber());
+    const ancestorAccountsMetas: AccountMeta[] = ancestorIds.map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const other = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(other.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const update = (payer: anchor.web3.Keypair | null) => {
+      const builder = program.methods.updateTrixel({ id: targetTrixelIdBN, value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: payer ? payer.publicKey : provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+        .remainingAccounts(ancestorAccountsMetas);
+      return payer ? builder.signers([payer]).rpc() : builder.rpc();
+    };
+
+    // Claiming the root trixel grants rights over every descendant
+    const rootId = new anchor.BN(ancestorIds[ancestorIds.length - 1]);
+    const rootPda = getTrixelPDA(worldKeypair.publicKey, rootId.toNumber(), program.programId)[0];
+    await program.methods.claimTrixel({ id: rootId })
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: rootPda, ownerTokenAccount: null })
+      .rpc();
+    await update(null);
+    try {
+      await update(other);
+      expect.fail("write beneath another owner's trixel should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("NotTrixelOwner");
+    }
+
+    await program.methods.transferTrixel({ id: rootId, newOwner: other.publicKey })
+      .accountsStrict({ owner: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: rootPda })
+      .rpc();
+    await update(other);
+
+    const trixelAccount = await fetchTrixel(program, targetTrixelPda);
+    // @ts-ignore
+    expect(trixelAccount.data.count.count).to.equal(2);
+  });
+
+  it("should grant an owner rights over its whole subtree and keep claims off owned land", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("ClaimAboveWorld".padEnd(32, "\0")));
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution: 5, dataType: { count: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: true, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    // Two leaves sharing every ancestor
+    const ownedId = VALID_IDS_RESOLUTION_5[0];
+    const siblingId = ownedId + 100000;
+    const ancestorIds = getTrixelAncestors(ownedId);
+    const ancestorAccountsMetas: AccountMeta[] = ancestorIds.map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    for (const id of [ownedId, siblingId]) {
+      await program.methods.createTrixelAndAncestors({ id: new anchor.BN(id) })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0], payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+        .remainingAccounts(ancestorAccountsMetas).rpc();
+    }
+
+    const other = anchor.web3.Keypair.generate();
+    const outsider = anchor.web3.Keypair.generate();
+    for (const keypair of [other, outsider]) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(keypair.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+      );
+    }
+    const update = (id: number, payer: anchor.web3.Keypair | null) => {
+      const builder = program.methods.updateTrixel({ id: new anchor.BN(id), value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0], payer: payer ? payer.publicKey : provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+        .remainingAccounts(ancestorAccountsMetas);
+      return payer ? builder.signers([payer]).rpc() : builder.rpc();
+    };
+    const expectRejected = async (attempt: Promise<unknown>, code: string, message: string) => {
+      let rejected = false;
+      try {
+        await attempt;
+      } catch (e: any) {
+        rejected = true;
+        expect(e.error?.errorCode?.code).to.equal(code);
+      }
+      expect(rejected, message).to.be.true;
+    };
+    const ownedPda = getTrixelPDA(worldKeypair.publicKey, ownedId, program.programId)[0];
+    const faceId = ancestorIds[ancestorIds.length - 1];
+    const facePda = getTrixelPDA(worldKeypair.publicKey, faceId, program.programId)[0];
+    const claimFace = (payer: anchor.web3.Keypair | null) => {
+      const builder = program.methods.claimTrixel({ id: new anchor.BN(faceId) })
+        .accountsStrict({ payer: payer ? payer.publicKey : provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: facePda, ownerTokenAccount: null });
+      return payer ? builder.signers([payer]).rpc() : builder.rpc();
+    };
+
+    // Once the leaf is owned, a claim on the face above it would reach into it
+    await program.methods.claimTrixel({ id: new anchor.BN(ownedId) })
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: ownedPda, ownerTokenAccount: null })
+      .remainingAccounts(ancestorAccountsMetas)
+      .rpc();
+    expect((await fetchTrixel(program, facePda)).ownedDescendants).to.equal(1);
+    await expectRejected(claimFace(other), "SubtreeOwned", "a claim above owned land should be rejected");
+
+    // Relinquishing the leaf frees the face
+    await program.methods.transferTrixel({ id: new anchor.BN(ownedId), newOwner: null })
+      .accountsStrict({ owner: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: ownedPda })
+      .remainingAccounts(ancestorAccountsMetas)
+      .rpc();
+    expect((await fetchTrixel(program, facePda)).ownedDescendants).to.equal(0);
+    await claimFace(null);
+
+    // The face's owner keeps its rights over a leaf claimed beneath it and handed to someone else
+    await program.methods.claimTrixel({ id: new anchor.BN(ownedId) })
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: ownedPda, ownerTokenAccount: null })
+      .remainingAccounts(ancestorAccountsMetas)
+      .rpc();
+    await program.methods.transferTrixel({ id: new anchor.BN(ownedId), newOwner: other.publicKey })
+      .accountsStrict({ owner: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: ownedPda })
+      .rpc();
+    await update(ownedId, other);
+    await update(ownedId, null);
+    await expectRejected(update(siblingId, other), "NotTrixelOwner", "the leaf's owner should not write its sibling");
+    await expectRejected(update(ownedId, outsider), "NotTrixelOwner", "a signer owning nothing above the leaf should not write it");
+
+    const owned = await fetchTrixel(program, ownedPda);
+    // @ts-ignore
+    expect(owned.data.count.count).to.equal(2);
+  });
+
+  it("should sell listed trixels at a fixed price and by auction", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("MarketWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { count: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: true, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorIds = getTrixelAncestors(targetTrixelIdBN.toNumber());
+    const ancestorAccountsMetas: AccountMeta[] = ancestorIds.map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const rootId = new anchor.BN(ancestorIds[ancestorIds.length - 1]);
+    const rootPda = getTrixelPDA(worldKeypair.publicKey, rootId.toNumber(), program.programId)[0];
+    const [listingPda] = PublicKey.findProgramAddressSync([Buffer.from("listing"), rootPda.toBuffer()], program.programId);
+    await program.methods.claimTrixel({ id: rootId })
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: rootPda, ownerTokenAccount: null })
+      .rpc();
+
+    const buyer = anchor.web3.Keypair.generate();
+    const bidder = anchor.web3.Keypair.generate();
+    for (const keypair of [buyer, bidder]) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(keypair.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+      );
+    }
+    const noTokenListing = { mint: null, listingTokenAccount: null, tokenProgram: null, associatedTokenProgram: null };
+
+    // Fixed price sale
+    const price = new anchor.BN(1_000_000);
+    await program.methods.listTrixel({ id: rootId, price, auctionDuration: null })
+      .accountsStrict({ seller: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: rootPda, listing: listingPda, systemProgram: SystemProgram.programId, ...noTokenListing, ownerTokenAccount: null })
+      .rpc();
+    expect((await fetchTrixel(program, rootPda)).owner.toBase58()).to.equal(listingPda.toBase58());
+
+    const sellerBalanceBefore = await provider.connection.getBalance(provider.wallet.publicKey);
+    await program.methods.buyTrixel({ id: rootId, maxPrice: price })
+      .accountsStrict({ buyer: buyer.publicKey, seller: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: rootPda, listing: listingPda, mint: null, buyerTokenAccount: null, sellerTokenAccount: null, tokenProgram: null, systemProgram: SystemProgram.programId })
+      .signers([buyer]).rpc();
+    expect((await fetchTrixel(program, rootPda)).owner.toBase58()).to.equal(buyer.publicKey.toBase58());
+    expect(await provider.connection.getBalance(provider.wallet.publicKey)).to.be.greaterThan(sellerBalanceBefore + price.toNumber());
+
+    // Auction, the new owner lists it back and the single bidder wins
+    await program.methods.listTrixel({ id: rootId, price, auctionDuration: new anchor.BN(2) })
+      .accountsStrict({ seller: buyer.publicKey, world: worldKeypair.publicKey, trixel: rootPda, listing: listingPda, systemProgram: SystemProgram.programId, ...noTokenListing, ownerTokenAccount: null })
+      .signers([buyer]).rpc();
+    try {
+      await program.methods.placeBid({ amount: price.subn(1) })
+        .accountsStrict({ bidder: bidder.publicKey, listing: listingPda, previousBidder: null, mint: null, bidderTokenAccount: null, listingTokenAccount: null, previousBidderTokenAccount: null, tokenProgram: null, systemProgram: SystemProgram.programId })
+        .signers([bidder]).rpc();
+      expect.fail("bid below the reserve price should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("BidTooLow");
+    }
+    await program.methods.placeBid({ amount: price })
+      .accountsStrict({ bidder: bidder.publicKey, listing: listingPda, previousBidder: null, mint: null, bidderTokenAccount: null, listingTokenAccount: null, previousBidderTokenAccount: null, tokenProgram: null, systemProgram: SystemProgram.programId })
+      .signers([bidder]).rpc();
+
+    await new Promise(resolve => setTimeout(resolve, 3000));
+    await program.methods.settleAuction({ id: rootId })
+      .accountsStrict({ payer: provider.wallet.publicKey, seller: buyer.publicKey, world: worldKeypair.publicKey, trixel: rootPda, listing: listingPda, mint: null, listingTokenAccount: null, sellerTokenAccount: null, tokenProgram: null })
+      .rpc();
+    expect((await fetchTrixel(program, rootPda)).owner.toBase58()).to.equal(bidder.publicKey.toBase58());
+    expect(await provider.connection.getAccountInfo(listingPda)).to.be.null;
+  });
+
+  it("should hand write rights to the holder of a minted trixel token", async () => {
+    const TOKEN_2022_PROGRAM_ID = new PublicKey("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+    const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("NftWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { count: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: true, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorIds = getTrixelAncestors(targetTrixelIdBN.toNumber());
+    const ancestorAccountsMetas: AccountMeta[] = ancestorIds.map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const rootId = new anchor.BN(ancestorIds[ancestorIds.length - 1]);
+    const rootPda = getTrixelPDA(worldKeypair.publicKey, rootId.toNumber(), program.programId)[0];
+    await program.methods.claimTrixel({ id: rootId })
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: rootPda, ownerTokenAccount: null })
+      .rpc();
+
+    const [mintPda] = PublicKey.findProgramAddressSync([Buffer.from("trixel_mint"), rootPda.toBuffer()], program.programId);
+    const [ownerTokenAccount] = PublicKey.findProgramAddressSync(
+      [provider.wallet.publicKey.toBuffer(), TOKEN_2022_PROGRAM_ID.toBuffer(), mintPda.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods.mintTrixelNft({ id: rootId, uri: "" })
+      .accountsStrict({ owner: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: rootPda, mint: mintPda, ownerTokenAccount, tokenProgram: TOKEN_2022_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId })
+      .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .rpc();
+    expect((await fetchTrixel(program, rootPda)).owner.toBase58()).to.equal(mintPda.toBase58());
+
+    const update = (tokenAccount: PublicKey | null) => program.methods.updateTrixel({ id: targetTrixelIdBN, value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: { ...NO_WRITE_ACCOUNTS, ownerTokenAccount: tokenAccount } })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    // The wallet alone no longer owns the trixel, holding the token does
+    try {
+      await update(null);
+      expect.fail("write without the trixel token should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("NotTrixelOwner");
+    }
+    await update(ownerTokenAccount);
+
+    const trixelAccount = await fetchTrixel(program, targetTrixelPda);
+    // @ts-ignore
+    expect(trixelAccount.data.count.count).to.equal(1);
+
+    // The token also stands in for the wallet when claiming beneath the minted trixel
+    const claim = (tokenAccount: PublicKey | null) => program.methods.claimTrixel({ id: targetTrixelIdBN })
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: targetTrixelPda, ownerTokenAccount: tokenAccount })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+    let rejected = false;
+    try {
+      await claim(null);
+    } catch (e: any) {
+      rejected = true;
+      expect(e.error?.errorCode?.code).to.equal("TrixelAlreadyOwned");
+    }
+    expect(rejected, "a claim beneath a minted trixel without its token should fail").to.be.true;
+    await claim(ownerTokenAccount);
+    expect((await fetchTrixel(program, targetTrixelPda)).owner.toBase58()).to.equal(provider.wallet.publicKey.toBase58());
+
+    // And when listing it, a cancelled listing gives the trixel back to its token
+    const [listingPda] = PublicKey.findProgramAddressSync([Buffer.from("listing"), rootPda.toBuffer()], program.programId);
+    const list = (tokenAccount: PublicKey | null) => program.methods.listTrixel({ id: rootId, price: new anchor.BN(1_000_000), auctionDuration: null })
+      .accountsStrict({ seller: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: rootPda, ownerTokenAccount: tokenAccount, listing: listingPda, mint: null, listingTokenAccount: null, tokenProgram: null, associatedTokenProgram: null, systemProgram: SystemProgram.programId })
+      .rpc();
+    rejected = false;
+    try {
+      await list(null);
+    } catch (e: any) {
+      rejected = true;
+      expect(e.error?.errorCode?.code).to.equal("NotTrixelOwner");
+    }
+    expect(rejected, "listing a minted trixel without its token should fail").to.be.true;
+    await list(ownerTokenAccount);
+    expect((await fetchTrixel(program, rootPda)).owner.toBase58()).to.equal(listingPda.toBase58());
+    await program.methods.cancelListing({ id: rootId })
+      .accountsStrict({ seller: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: rootPda, listing: listingPda, listingTokenAccount: null, mint: null, sellerTokenAccount: null, tokenProgram: null })
+      .rpc();
+    expect((await fetchTrixel(program, rootPda)).owner.toBase58()).to.equal(mintPda.toBase58());
+  });
+
+  it("should close a token auction's escrow when it is cancelled", async () => {
+    const TOKEN_2022_PROGRAM_ID = new PublicKey("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+    const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("TokenAuctionWorld".padEnd(32, "\0")));
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution: 5, dataType: { count: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: true, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    // Two faces, one minted to give the auction a currency and one to auction
+    const claimFace = async (id: number) => {
+      const ancestorIds = getTrixelAncestors(id);
+      await program.methods.createTrixelAndAncestors({ id: new anchor.BN(id) })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0], payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+        .remainingAccounts(ancestorIds.map(ancestorId => ({ pubkey: getTrixelPDA(worldKeypair.publicKey, ancestorId, program.programId)[0], isSigner: false, isWritable: true })))
+        .rpc();
+      const faceId = ancestorIds[ancestorIds.length - 1];
+      const facePda = getTrixelPDA(worldKeypair.publicKey, faceId, program.programId)[0];
+      await program.methods.claimTrixel({ id: new anchor.BN(faceId) })
+        .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: facePda, ownerTokenAccount: null })
+        .rpc();
+      return { faceId: new anchor.BN(faceId), facePda };
+    };
+    const minted = await claimFace(211111);
+    const [mintPda] = PublicKey.findProgramAddressSync([Buffer.from("trixel_mint"), minted.facePda.toBuffer()], program.programId);
+    const [ownerTokenAccount] = PublicKey.findProgramAddressSync(
+      [provider.wallet.publicKey.toBuffer(), TOKEN_2022_PROGRAM_ID.toBuffer(), mintPda.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods.mintTrixelNft({ id: minted.faceId, uri: "" })
+      .accountsStrict({ owner: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: minted.facePda, mint: mintPda, ownerTokenAccount, tokenProgram: TOKEN_2022_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId })
+      .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .rpc();
+
+    const { faceId, facePda } = await claimFace(211112);
+    const [listingPda] = PublicKey.findProgramAddressSync([Buffer.from("listing"), facePda.toBuffer()], program.programId);
+    const [listingTokenAccount] = PublicKey.findProgramAddressSync(
+      [listingPda.toBuffer(), TOKEN_2022_PROGRAM_ID.toBuffer(), mintPda.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const list = (duration = 600) => program.methods.listTrixel({ id: faceId, price: new anchor.BN(1), auctionDuration: new anchor.BN(duration) })
+      .accountsStrict({ seller: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: facePda, listing: listingPda, mint: mintPda, listingTokenAccount, tokenProgram: TOKEN_2022_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId, ownerTokenAccount: null })
+      .rpc();
+    const cancel = (escrow: PublicKey | null, sellerTokenAccount: PublicKey | null = null) => program.methods.cancelListing({ id: faceId })
+      .accountsStrict({ seller: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: facePda, listing: listingPda, listingTokenAccount: escrow, mint: sellerTokenAccount ? mintPda : null, sellerTokenAccount, tokenProgram: TOKEN_2022_PROGRAM_ID })
+      .rpc();
+
+    await list();
+    expect(await provider.connection.getAccountInfo(listingTokenAccount)).to.not.be.null;
+
+    // The escrow can't be left behind, or listing again would fail to create it
+    try {
+      await cancel(null);
+      expect.fail("cancelling without the escrow should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("MissingTokenAccount");
+    }
+    await cancel(listingTokenAccount);
+    expect(await provider.connection.getAccountInfo(listingTokenAccount)).to.be.null;
+    expect(await provider.connection.getAccountInfo(listingPda)).to.be.null;
+
+    await list();
+    await cancel(listingTokenAccount);
+    expect((await fetchTrixel(program, facePda)).owner.toBase58()).to.equal(provider.wallet.publicKey.toBase58());
+
+    // Anyone can send tokens to the escrow, which go to the seller instead of keeping it from closing
+    const sendToEscrow = () => provider.sendAndConfirm(new anchor.web3.Transaction().add(new anchor.web3.TransactionInstruction({
+      programId: TOKEN_2022_PROGRAM_ID,
+      keys: [
+        { pubkey: ownerTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: mintPda, isSigner: false, isWritable: false },
+        { pubkey: listingTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: provider.wallet.publicKey, isSigner: true, isWritable: false },
+      ],
+      // TransferChecked of a single token with no decimals
+      data: Buffer.from([12, ...new anchor.BN(1).toArray("le", 8), 0]),
+    })));
+    const tokenBalance = async (account: PublicKey) => Number((await provider.connection.getTokenAccountBalance(account)).value.amount);
+
+    await list();
+    await sendToEscrow();
+    await cancel(listingTokenAccount, ownerTokenAccount);
+    expect(await tokenBalance(ownerTokenAccount)).to.equal(1);
+    expect(await provider.connection.getAccountInfo(listingTokenAccount)).to.be.null;
+
+    await list(2);
+    await sendToEscrow();
+    await new Promise(resolve => setTimeout(resolve, 3000));
+    await program.methods.settleAuction({ id: faceId })
+      .accountsStrict({ payer: provider.wallet.publicKey, seller: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: facePda, listing: listingPda, mint: mintPda, listingTokenAccount, sellerTokenAccount: ownerTokenAccount, tokenProgram: TOKEN_2022_PROGRAM_ID })
+      .rpc();
+    expect(await tokenBalance(ownerTokenAccount)).to.equal(1);
+    expect(await provider.connection.getAccountInfo(listingTokenAccount)).to.be.null;
+    expect((await fetchTrixel(program, facePda)).owner.toBase58()).to.equal(provider.wallet.publicKey.toBase58());
+  });
+
+  it("should revert a challenged write and slash the updater's bond", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("DisputeWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { aggregateAccumulate: {} };
+    const permissionedUpdates = false;
+    const disputeBond = new anchor.BN(1_000_000);
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: null, challengeWindow: new anchor.BN(600), disputeBond, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorIds = getTrixelAncestors(targetTrixelIdBN.toNumber());
+    const ancestorAccountsMetas: AccountMeta[] = ancestorIds.map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const updateIndex = new anchor.BN(0);
+    const [updateRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("update"), targetTrixelPda.toBuffer(), updateIndex.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: 5, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: { ...NO_WRITE_ACCOUNTS, updateRecord: updateRecordPda } })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const challenger = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(challenger.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const [challengePda] = PublicKey.findProgramAddressSync([Buffer.from("challenge"), updateRecordPda.toBuffer()], program.programId);
+    await program.methods.challengeUpdate({ updateIndex, bond: disputeBond })
+      .accountsStrict({ challenger: challenger.publicKey, world: worldKeypair.publicKey, trixel: targetTrixelPda, updateRecord: updateRecordPda, challenge: challengePda, systemProgram: SystemProgram.programId })
+      .signers([challenger]).rpc();
+
+    const challengerBalanceBefore = await provider.connection.getBalance(challenger.publicKey);
+    await program.methods.resolveChallenge({ upheld: true })
+      .accountsStrict({ resolver: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: targetTrixelPda, updateRecord: updateRecordPda, challenge: challengePda, challenger: challenger.publicKey, updater: provider.wallet.publicKey })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    // The challenger gets both bonds back, the write is rolled back everywhere
+    expect(await provider.connection.getBalance(challenger.publicKey)).to.be.greaterThan(challengerBalanceBefore + 2 * disputeBond.toNumber());
+    const trixelAccount = await fetchTrixel(program, targetTrixelPda);
+    // @ts-ignore
+    expect(trixelAccount.data.aggregateAccumulate.metric.toNumber()).to.equal(0);
+    const worldAccount = await program.account.world.fetch(worldKeypair.publicKey);
+    // @ts-ignore
+    expect(worldAccount.data.aggregateAccumulate.metric.toNumber()).to.equal(0);
+    expect(await provider.connection.getAccountInfo(updateRecordPda)).to.be.null;
+  });
+
+  it("should refund both bonds when a challenged write can no longer be reverted", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("StaleDisputeWorld".padEnd(32, "\0")));
+    const disputeBond = new anchor.BN(10_000_000);
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution: 1, dataType: { aggregateOverwrite: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: null, challengeWindow: new anchor.BN(600), disputeBond, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const trixelMeta = (id: number): AccountMeta => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    });
+    const recordPda = (id: number, index: number) => PublicKey.findProgramAddressSync(
+      [Buffer.from("update"), trixelMeta(id).pubkey.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+    const challengePda = (id: number, index: number) => PublicKey.findProgramAddressSync(
+      [Buffer.from("challenge"), recordPda(id, index).toBuffer()],
+      program.programId
+    )[0];
+    const updateTrixel = (id: number, index: number, value: number) => program.methods
+      .updateTrixel({ id: new anchor.BN(id), value, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: { ...NO_WRITE_ACCOUNTS, updateRecord: recordPda(id, index) } })
+      .remainingAccounts([trixelMeta(5)])
+      .rpc();
+    const challenger = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(challenger.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const challenge = (id: number, index: number) => program.methods
+      .challengeUpdate({ updateIndex: new anchor.BN(index), bond: disputeBond })
+      .accountsStrict({ challenger: challenger.publicKey, world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, updateRecord: recordPda(id, index), challenge: challengePda(id, index), systemProgram: SystemProgram.programId })
+      .signers([challenger]).rpc();
+    const resolve = (id: number, index: number) => program.methods
+      .resolveChallenge({ upheld: true })
+      .accountsStrict({ resolver: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, updateRecord: recordPda(id, index), challenge: challengePda(id, index), challenger: challenger.publicKey, updater: provider.wallet.publicKey })
+      .remainingAccounts([trixelMeta(5)])
+      .rpc();
+
+    for (const id of [15, 25]) {
+      await program.methods.createTrixelAndAncestors({ id: new anchor.BN(id) })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+        .remainingAccounts([trixelMeta(5)]).rpc();
+    }
+    await updateTrixel(15, 0, 5);
+    await updateTrixel(15, 1, 7);
+    await updateTrixel(25, 0, 3);
+    await challenge(15, 0);
+    await challenge(25, 0);
+
+    // An overwrite that has been superseded is left in place, and the challenger
+    // only gets its own bond back
+    let challengerBalanceBefore = await provider.connection.getBalance(challenger.publicKey);
+    await resolve(15, 0);
+    let challengerGain = await provider.connection.getBalance(challenger.publicKey) - challengerBalanceBefore;
+    expect(challengerGain).to.be.greaterThan(disputeBond.toNumber());
+    expect(challengerGain).to.be.lessThan(2 * disputeBond.toNumber());
+    // @ts-ignore
+    expect((await fetchTrixel(program, trixelMeta(15).pubkey)).data.aggregateOverwrite.metric.toNumber()).to.equal(7);
+    expect(await provider.connection.getAccountInfo(recordPda(15, 0))).to.be.null;
+
+    // Writes can't be reverted while the world's resolution is changing
+    await program.methods.startResolutionChange({ targetResolution: 2, splitRule: { divide: {} } })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+    let rejected = false;
+    try {
+      await resolve(25, 0);
+    } catch (e: any) {
+      rejected = true;
+      expect(e.error?.errorCode?.code).to.equal("ResolutionChangeInProgress");
+    }
+    expect(rejected, "resolving during a resolution change should fail").to.be.true;
+
+    // Nor once the trixel has been refined and its data moved to its children
+    const withChildren = (id: number) => [id, ...[1, 2, 3, 4].map(digit => digit * 10 ** String(id).length + id)].map(trixelMeta);
+    await program.methods.retargetTrixels({ ids: [new anchor.BN(15), new anchor.BN(25)] })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey, systemProgram: SystemProgram.programId })
+      .remainingAccounts([...withChildren(15), ...withChildren(25)])
+      .rpc();
+    await program.methods.rehashTrixels({ ids: [new anchor.BN(5)] })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .remainingAccounts(withChildren(5))
+      .rpc();
+    await program.methods.finishResolutionChange()
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .remainingAccounts([1, 2, 3, 4, 5, 6, 7, 8].map(trixelMeta))
+      .rpc();
+    challengerBalanceBefore = await provider.connection.getBalance(challenger.publicKey);
+    await resolve(25, 0);
+    challengerGain = await provider.connection.getBalance(challenger.publicKey) - challengerBalanceBefore;
+    expect(challengerGain).to.be.greaterThan(disputeBond.toNumber());
+    expect(challengerGain).to.be.lessThan(2 * disputeBond.toNumber());
+    // @ts-ignore
+    expect((await fetchTrixel(program, trixelMeta(25).pubkey)).data.aggregateOverwrite.metric.toNumber()).to.equal(3);
+    // @ts-ignore
+    expect((await program.account.world.fetch(worldKeypair.publicKey)).data.aggregateOverwrite.metric.toNumber()).to.equal(10);
+  });
+
+  it("should require a stake from updaters and let the authority slash it", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("StakeWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { count: {} };
+    const permissionedUpdates = false;
+    const minStake = new anchor.BN(5_000_000);
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: null, challengeWindow: null, disputeBond: null, arbiter: null, minStake, stakeMint: null, unbondingPeriod: new anchor.BN(3600), variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorIds = getTrixelAncestors(targetTrixelIdBN.toNumber());
+    const ancestorAccountsMetas: AccountMeta[] = ancestorIds.map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const reporter = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(reporter.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const [stakePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), worldKeypair.publicKey.toBuffer(), reporter.publicKey.toBuffer()],
+      program.programId
+    );
+    const update = (stake: PublicKey | null) => program.methods.updateTrixel({ id: targetTrixelIdBN, value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: reporter.publicKey, systemProgram: SystemProgram.programId, write: { ...NO_WRITE_ACCOUNTS, stake } })
+      .remainingAccounts(ancestorAccountsMetas).signers([reporter]).rpc();
+
+    try {
+      await update(null);
+      expect.fail("write without a stake should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("InsufficientStake");
+    }
+
+    await program.methods.depositStake({ amount: minStake })
+      .accountsStrict({ staker: reporter.publicKey, world: worldKeypair.publicKey, stake: stakePda, mint: null, stakerTokenAccount: null, stakeTokenAccount: null, tokenProgram: null, associatedTokenProgram: null, systemProgram: SystemProgram.programId })
+      .signers([reporter]).rpc();
+    await update(stakePda);
+
+    // Slashing drops the reporter below the minimum
+    const [treasuryPda] = PublicKey.findProgramAddressSync([Buffer.from("treasury"), worldKeypair.publicKey.toBuffer()], program.programId);
+    await program.methods.slashStake({ amount: new anchor.BN(1) })
+      .accountsStrict({ slasher: provider.wallet.publicKey, world: worldKeypair.publicKey, stake: stakePda, treasury: treasuryPda, mint: null, stakeTokenAccount: null, treasuryTokenAccount: null, tokenProgram: null, systemProgram: SystemProgram.programId })
+      .rpc();
+    try {
+      await update(stakePda);
+      expect.fail("write below the minimum stake should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("InsufficientStake");
+    }
+
+    // Unbonded stake stays locked until the unbonding period has passed
+    await program.methods.requestUnstake({ amount: minStake.subn(1) })
+      .accountsStrict({ staker: reporter.publicKey, world: worldKeypair.publicKey, stake: stakePda })
+      .signers([reporter]).rpc();
+    try {
+      await program.methods.withdrawStake()
+        .accountsStrict({ staker: reporter.publicKey, world: worldKeypair.publicKey, stake: stakePda, mint: null, stakeTokenAccount: null, stakerTokenAccount: null, tokenProgram: null })
+        .signers([reporter]).rpc();
+      expect.fail("withdrawal during unbonding should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("StakeUnbonding");
+    }
+  });
+
+  it("should require a stake to commit and to reveal updates", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("StakedRevealWorld".padEnd(32, "\0")));
+    const minStake = new anchor.BN(5_000_000);
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution: 5, dataType: { count: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: new anchor.BN(600), minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: null, challengeWindow: null, disputeBond: null, arbiter: null, minStake, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorAccountsMetas: AccountMeta[] = getTrixelAncestors(targetTrixelIdBN.toNumber()).map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const reporter = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(reporter.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const [stakePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), worldKeypair.publicKey.toBuffer(), reporter.publicKey.toBuffer()],
+      program.programId
+    );
+    const salt = randomBytes(32);
+    const valueBytes = Buffer.alloc(4);
+    valueBytes.writeInt32LE(1);
+    const hash = createHash("sha256")
+      .update(worldKeypair.publicKey.toBuffer())
+      .update(reporter.publicKey.toBuffer())
+      .update(targetTrixelIdBN.toArrayLike(Buffer, "le", 8))
+      .update(valueBytes)
+      .update(salt)
+      .digest();
+    const [commitmentPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("commitment"), worldKeypair.publicKey.toBuffer(), reporter.publicKey.toBuffer(), hash],
+      program.programId
+    );
+    const commit = (stake: PublicKey | null) => program.methods.commitTrixelUpdate({ hash: Array.from(hash) })
+      .accountsStrict({ payer: reporter.publicKey, world: worldKeypair.publicKey, commitment: commitmentPda, systemProgram: SystemProgram.programId, stake })
+      .signers([reporter]).rpc();
+
+    let rejected = false;
+    try {
+      await commit(null);
+    } catch (e: any) {
+      rejected = true;
+      expect(e.error?.errorCode?.code).to.equal("InsufficientStake");
+    }
+    expect(rejected, "commit without a stake should fail").to.be.true;
+
+    await program.methods.depositStake({ amount: minStake })
+      .accountsStrict({ staker: reporter.publicKey, world: worldKeypair.publicKey, stake: stakePda, mint: null, stakerTokenAccount: null, stakeTokenAccount: null, tokenProgram: null, associatedTokenProgram: null, systemProgram: SystemProgram.programId })
+      .signers([reporter]).rpc();
+    await commit(stakePda);
+
+    // A stake slashed below the minimum between commit and reveal blocks the reveal
+    const [treasuryPda] = PublicKey.findProgramAddressSync([Buffer.from("treasury"), worldKeypair.publicKey.toBuffer()], program.programId);
+    await program.methods.slashStake({ amount: new anchor.BN(1) })
+      .accountsStrict({ slasher: provider.wallet.publicKey, world: worldKeypair.publicKey, stake: stakePda, treasury: treasuryPda, mint: null, stakeTokenAccount: null, treasuryTokenAccount: null, tokenProgram: null, systemProgram: SystemProgram.programId })
+      .rpc();
+    rejected = false;
+    try {
+      await program.methods.revealTrixelUpdate({ id: targetTrixelIdBN, value: 1, salt: Array.from(salt) })
+        .accountsStrict({ payer: reporter.publicKey, world: worldKeypair.publicKey, trixel: targetTrixelPda, commitment: commitmentPda, systemProgram: SystemProgram.programId, write: { ...NO_WRITE_ACCOUNTS, stake: stakePda } })
+        .remainingAccounts(ancestorAccountsMetas).signers([reporter]).rpc();
+    } catch (e: any) {
+      rejected = true;
+      expect(e.error?.errorCode?.code).to.equal("InsufficientStake");
+    }
+    expect(rejected, "reveal below the minimum stake should fail").to.be.true;
+    expect(await provider.connection.getAccountInfo(commitmentPda)).to.not.be.null;
+  });
+
+  it("should return a trixel's value to callers", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("ReadWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { count: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorIds = getTrixelAncestors(targetTrixelIdBN.toNumber());
+    const ancestorAccountsMetas: AccountMeta[] = ancestorIds.map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+    await program.methods.updateTrixel({ id: targetTrixelIdBN, value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const value = await program.methods.getTrixelValue({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda })
+      .view();
+    const trixelAccount = await fetchTrixel(program, targetTrixelPda);
+    expect(value.id.toNumber()).to.equal(targetTrixelIdBN.toNumber());
+    expect(value.resolution).to.equal(canonicalResolution);
+    expect(value.data.count.count).to.equal(1);
+    expect(Buffer.from(value.hash).equals(Buffer.from(trixelAccount.hash))).to.be.true;
+  });
+
+  it("should reject conditional writes to a trixel that has changed", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("CasWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { aggregateOverwrite: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const targetTrixelIdBN = new anchor.BN(VALID_IDS_RESOLUTION_5[0]);
+    const [targetTrixelPda] = getTrixelPDA(worldKeypair.publicKey, targetTrixelIdBN.toNumber(), program.programId);
+    const ancestorIds = getTrixelAncestors(targetTrixelIdBN.toNumber());
+    const ancestorAccountsMetas: AccountMeta[] = ancestorIds.map(id => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    }));
+    await program.methods.createTrixelAndAncestors({ id: targetTrixelIdBN })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const update = (value: number, expectedUpdates: anchor.BN | null, expectedHash: number[] | null) => program.methods
+      .updateTrixel({ id: targetTrixelIdBN, value, coords: null, expectedUpdates, expectedHash })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: targetTrixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    await update(10, new anchor.BN(0), null);
+    try {
+      await update(20, new anchor.BN(0), null);
+      expect.fail("write against a stale update count should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("TrixelChanged");
+    }
+
+    const current = await fetchTrixel(program, targetTrixelPda);
+    await update(30, null, Array.from(current.hash));
+    try {
+      await update(40, null, Array.from(current.hash));
+      expect.fail("write against a stale hash should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("TrixelChanged");
+    }
+
+    const trixelAccount = await fetchTrixel(program, targetTrixelPda);
+    // @ts-ignore
+    expect(trixelAccount.data.aggregateOverwrite.metric.toNumber()).to.equal(30);
+  });
+
+  it("should update the trixel derived from a point", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("PointWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { count: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const point = { ra: 123.0, dec: 45.0 };
+    const { trixelId, trixelPda, ancestorPDAs } = getTrixelAndAncestorPDAs(point, canonicalResolution, worldKeypair.publicKey, program.programId);
+    const ancestorAccountsMetas: AccountMeta[] = ancestorPDAs.map(pubkey => ({ pubkey, isSigner: false, isWritable: true }));
+    await program.methods.createTrixelAndAncestors({ id: new anchor.BN(trixelId) })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    const updateAt = (coords: SphericalCoords) => program.methods
+      .updateAtPoint({ coords, value: 1 })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(ancestorAccountsMetas).rpc();
+
+    await updateAt(point);
+    const trixelAccount = await fetchTrixel(program, trixelPda);
+    expect(trixelAccount.data.count.count).to.equal(1);
+
+    // A point outside the trixel resolves to a different account
+    try {
+      await updateAt({ ra: 300.0, dec: -45.0 });
+      expect.fail("update at a point outside the trixel should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("InvalidTrixelAccount");
+    }
+  });
+
+  it("should bin ingested points into canonical trixels", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("IngestWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    const dataType = { aggregateAccumulate: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    // Two points share a trixel, the third lands in another
+    const points = [
+      { coords: { ra: 123.0, dec: 45.0 }, value: 5 },
+      { coords: { ra: 300.0, dec: -45.0 }, value: 7 },
+      { coords: { ra: 123.0001, dec: 45.0001 }, value: 11 },
+    ];
+    const bins = [points[0], points[1]].map(point =>
+      getTrixelAndAncestorPDAs(point.coords, canonicalResolution, worldKeypair.publicKey, program.programId)
+    );
+    for (const { trixelId, trixelPda, ancestorPDAs } of bins) {
+      await program.methods.createTrixelAndAncestors({ id: new anchor.BN(trixelId) })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+        .remainingAccounts(ancestorPDAs.map(pubkey => ({ pubkey, isSigner: false, isWritable: true }))).rpc();
+    }
+
+    const remainingAccounts: AccountMeta[] = bins.flatMap(({ trixelPda, ancestorPDAs }) =>
+      [trixelPda, ...ancestorPDAs].map(pubkey => ({ pubkey, isSigner: false, isWritable: true }))
+    );
+    await program.methods
+      .ingestPoints({ points })
+      .accountsStrict({ world: worldKeypair.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(remainingAccounts).rpc();
+
+    const first = await fetchTrixel(program, bins[0].trixelPda);
+    const second = await fetchTrixel(program, bins[1].trixelPda);
+    // @ts-ignore
+    expect(first.data.aggregateAccumulate.metric.toNumber()).to.equal(16);
+    expect(first.updates.toNumber()).to.equal(1);
+    // @ts-ignore
+    expect(second.data.aggregateAccumulate.metric.toNumber()).to.equal(7);
+    const worldAccount = await program.account.world.fetch(worldKeypair.publicKey);
+    // @ts-ignore
+    expect(worldAccount.data.aggregateAccumulate.metric.toNumber()).to.equal(23);
+  });
+
+  it("should charge and rate-limit ingested points once per bin", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("IngestLimitWorld".padEnd(32, "\0")));
+    const canonicalResolution = 5;
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType: { count: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: 2, paused: null, claimsEnabled: null, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+    const [treasuryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), worldKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    const feeAmount = 5000;
+    await program.methods
+      .setFeeSchedule({ feeSchedule: { mint: null, amount: new anchor.BN(feeAmount), scaling: { fixed: {} } } })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey, treasury: treasuryPda, systemProgram: SystemProgram.programId })
+      .rpc();
+    const [reporterPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reporter"), worldKeypair.publicKey.toBuffer(), provider.wallet.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods.registerReporter()
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, reporter: reporterPda, systemProgram: SystemProgram.programId })
+      .rpc();
+    const write = {
+      ...NO_WRITE_ACCOUNTS,
+      fee: { ...NO_FEE_ACCOUNTS, treasury: treasuryPda },
+      reporter: { ...NO_REPORTER_ACCOUNTS, record: reporterPda },
+    };
+
+    // Three points in two bins
+    const points = [
+      { coords: { ra: 123.0, dec: 45.0 }, value: 1 },
+      { coords: { ra: 300.0, dec: -45.0 }, value: 1 },
+      { coords: { ra: 123.0001, dec: 45.0001 }, value: 1 },
+    ];
+    const bins = [points[0], points[1]].map(point =>
+      getTrixelAndAncestorPDAs(point.coords, canonicalResolution, worldKeypair.publicKey, program.programId)
+    );
+    for (const { trixelId, trixelPda, ancestorPDAs } of bins) {
+      await program.methods.createTrixelAndAncestors({ id: new anchor.BN(trixelId) })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: write.fee })
+        .remainingAccounts(ancestorPDAs.map(pubkey => ({ pubkey, isSigner: false, isWritable: true }))).rpc();
+    }
+    const chain = ({ trixelPda, ancestorPDAs }: typeof bins[number]): AccountMeta[] =>
+      [trixelPda, ...ancestorPDAs].map(pubkey => ({ pubkey, isSigner: false, isWritable: true }));
+
+    const treasuryBalanceBefore = await provider.connection.getBalance(treasuryPda);
+    await program.methods
+      .ingestPoints({ points })
+      .accountsStrict({ world: worldKeypair.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write })
+      .remainingAccounts(bins.flatMap(chain)).rpc();
+
+    // Each bin is one write, charged and counted once
+    expect(await provider.connection.getBalance(treasuryPda) - treasuryBalanceBefore).to.equal(2 * feeAmount);
+    const reporterRecord = await program.account.reporter.fetch(reporterPda);
+    expect(reporterRecord.epochUpdates).to.equal(2);
+    // @ts-ignore
+    expect((await fetchTrixel(program, bins[0].trixelPda)).data.count.count).to.equal(2);
+
+    let rejected = false;
+    try {
+      await program.methods
+        .ingestPoints({ points: [points[1]] })
+        .accountsStrict({ world: worldKeypair.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write })
+        .remainingAccounts(chain(bins[1])).rpc();
+    } catch (e: any) {
+      rejected = true;
+      expect(e.error?.errorCode?.code).to.equal("RateLimitExceeded");
+    }
+    expect(rejected, "a third write in the epoch should fail").to.be.true;
+  });
+
+  it("should sum a region from its coarsest trixels", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("RegionWorld".padEnd(32, "\0")));
+    const canonicalResolution = 2;
+    const dataType = { count: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const trixelMeta = (id: number): AccountMeta => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    });
+    for (const id of [115, 215]) {
+      const ancestors = getTrixelAncestors(id).map(trixelMeta);
+      await program.methods.createTrixelAndAncestors({ id: new anchor.BN(id) })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+        .remainingAccounts(ancestors).rpc();
+      await program.methods.updateTrixel({ id: new anchor.BN(id), value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+        .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+        .remainingAccounts(ancestors).rpc();
+    }
+
+    const regionValue = (ids: number[]) => program.methods
+      .getRegionValue({ ids: ids.map(id => new anchor.BN(id)) })
+      .accountsStrict({ world: worldKeypair.publicKey })
+      .remainingAccounts(ids.map(id => ({ ...trixelMeta(id), isWritable: false })))
+      .view();
+
+    const whole = await regionValue([15]);
+    expect(whole.trixels).to.equal(1);
+    expect(whole.data.count.count).to.equal(2);
+    const parts = await regionValue([115, 215]);
+    expect(parts.data.count.count).to.equal(2);
+
+    // Overlapping trixels would be counted twice
+    try {
+      await regionValue([115, 15]);
+      expect.fail("a region that isn't compacted should be rejected");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("InvalidArgument");
+    }
+  });
+
+  it("should count trixels that were never written as zero in a region", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("SparseRegionWorld".padEnd(32, "\0")));
+    const canonicalResolution = 2;
+    const dataType = { count: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const trixelMeta = (id: number): AccountMeta => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: false,
+    });
+    const ancestors = getTrixelAncestors(115).map(id => ({ ...trixelMeta(id), isWritable: true }));
+    await program.methods.createTrixelAndAncestors({ id: new anchor.BN(115) })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(115).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestors).rpc();
+    await program.methods.updateTrixel({ id: new anchor.BN(115), value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(115).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(ancestors).rpc();
+
+    const regionValue = (ids: number[], accounts: AccountMeta[]) => program.methods
+      .getRegionValue({ ids: ids.map(id => new anchor.BN(id)) })
+      .accountsStrict({ world: worldKeypair.publicKey })
+      .remainingAccounts(accounts)
+      .view();
+
+    // 215 and 315 have no accounts yet, which their parent 15 shows by having no
+    // child there. Face 2 has never been written at all
+    const sparse = await regionValue([2, 115, 215, 315], [2, 115, 215, 15, 315, 15].map(trixelMeta));
+    expect(sparse.trixels).to.equal(4);
+    expect(sparse.data.count.count).to.equal(1);
+
+    // An empty account only stands in for the PDA of its own id
+    let rejected = false;
+    try {
+      await regionValue([115, 215], [trixelMeta(115), trixelMeta(415)]);
+    } catch (e: any) {
+      rejected = true;
+      expect(e.toString()).to.contain("InvalidTrixelAccount");
+    }
+    expect(rejected, "an unrelated empty account should be rejected").to.be.true;
+
+    // A written leaf holds the data of everything beneath it
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: null, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: true })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+    const coarseAncestors = getTrixelAncestors(16).map(id => ({ ...trixelMeta(id), isWritable: true }));
+    await program.methods.createTrixelAndAncestors({ id: new anchor.BN(16) })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(16).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(coarseAncestors).rpc();
+    expect((await regionValue([116], [116, 16].map(trixelMeta))).data.count.count).to.equal(0);
+    await program.methods.updateTrixel({ id: new anchor.BN(16), value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(16).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(coarseAncestors).rpc();
+    rejected = false;
+    try {
+      await regionValue([116], [116, 16].map(trixelMeta));
+    } catch (e: any) {
+      rejected = true;
+      expect(e.toString()).to.contain("ResolutionConflict");
+    }
+    expect(rejected, "a trixel beneath a written leaf should be rejected").to.be.true;
+
+    // Nor can a farther ancestor stand in for the closest one
+    rejected = false;
+    try {
+      await regionValue([215], [215, 5].map(trixelMeta));
+    } catch (e: any) {
+      rejected = true;
+      expect(e.toString()).to.contain("InvalidArgument");
+    }
+    expect(rejected, "an ancestor with a child on the way should be rejected").to.be.true;
+  });
+
+  it("should update a deep trixel and its ancestors within the default compute budget", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("DeepWorld".padEnd(32, "\0")));
+    const canonicalResolution = 10;
+    const dataType = { meanAccumulate: {} };
+    const permissionedUpdates = false;
+
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType, permissionedUpdates })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const coords = { ra: 45.0, dec: 45.0 };
+    const { trixelId, trixelPda, ancestorPDAs } = getTrixelAndAncestorPDAs(coords, canonicalResolution, worldKeypair.publicKey, program.programId);
+    const ancestors: AccountMeta[] = ancestorPDAs.map(pubkey => ({ pubkey, isSigner: false, isWritable: true }));
+    await program.methods.createTrixelAndAncestors({ id: new anchor.BN(trixelId) })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestors)
+      .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+      .rpc();
+
+    const signature = await program.methods.updateTrixel({ id: new anchor.BN(trixelId), value: 5, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(ancestors)
+      .rpc({ commitment: "confirmed" });
+
+    // A guard on the budget of the deepest write. Legacy trixels can't be written
+    // until migrated, see the migrate_trixel test
+    const tx = await provider.connection.getTransaction(signature, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+    expect(tx.meta.computeUnitsConsumed).to.be.lessThan(200_000);
+
+    const trixel = await fetchTrixel(program, trixelPda);
+    // @ts-ignore
+    expect(trixel.data.meanAccumulate.numerator.toNumber()).to.equal(5);
+
+    // Each trixel stores its bump so writes check addresses without searching for them
+    for (const id of [trixelId, ...getTrixelAncestors(trixelId)]) {
+      const [pda, bump] = getTrixelPDA(worldKeypair.publicKey, id, program.programId);
+      expect((await fetchTrixel(program, pda)).bump).to.equal(bump);
+    }
+  });
+
+  it("should create a world at the PDA of its authority and name", async () => {
+    const name = "NamedWorld";
+    const worldNameArray = Array.from(Buffer.from(name.padEnd(32, "\0")));
+    const [worldPda] = getWorldPDA(provider.wallet.publicKey, name, program.programId);
+    const createNamedWorld = () => program.methods
+      .createNamedWorld({ name: worldNameArray, canonicalResolution: 2, dataType: { count: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldPda, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+
+    await createNamedWorld();
+    const worldAccount = await program.account.world.fetch(worldPda);
+    expect(worldAccount.authority).to.eql(provider.wallet.publicKey);
+    expect(Buffer.from(worldAccount.name).toString().replace(/\0+$/, "")).to.equal(name);
+
+    // The name is taken for this authority
+    let rejected = false;
+    try {
+      await createNamedWorld();
+    } catch (e: any) {
+      rejected = true;
+    }
+    expect(rejected, "a second world with the same name should be rejected").to.be.true;
+  });
+
+  it("should set and resize a world's metadata", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("MetadataWorld".padEnd(32, "\0")));
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution: 2, dataType: { meanOverwrite: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const [worldMetadata] = PublicKey.findProgramAddressSync(
+      [Buffer.from("world_metadata"), worldKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    const setWorldMetadata = (description: string, units = "ppm") => program.methods
+      .setWorldMetadata({ units, decimals: 2, description, license: "CC-BY-4.0", uri: "https://example.com/schema.json" })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey, worldMetadata, systemProgram: SystemProgram.programId })
+      .rpc();
+
+    await setWorldMetadata("Air quality");
+    let metadata = await program.account.worldMetadata.fetch(worldMetadata);
+    expect(metadata.units).to.equal("ppm");
+    expect(metadata.decimals).to.equal(2);
+    expect(metadata.description).to.equal("Air quality");
+    const initialSize = (await provider.connection.getAccountInfo(worldMetadata)).data.length;
+
+    const longer = "Hourly PM2.5 readings from community sensors";
+    await setWorldMetadata(longer);
+    metadata = await program.account.worldMetadata.fetch(worldMetadata);
+    expect(metadata.description).to.equal(longer);
+    const grownSize = (await provider.connection.getAccountInfo(worldMetadata)).data.length;
+    expect(grownSize).to.equal(initialSize + longer.length - "Air quality".length);
+
+    try {
+      await setWorldMetadata(longer, "micrograms per cubic metre");
+      expect.fail("oversized units should be rejected");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("InvalidArgument");
+    }
+  });
+
+  it("should keep trixels within a world's bounds and root its tree there", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("BoundedWorld".padEnd(32, "\0")));
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution: 2, dataType: { count: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods
+      .setWorldBounds({ roots: [new anchor.BN(15)], rooted: true })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const trixelMeta = (id: number): AccountMeta => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    });
+    const createTrixel = (id: number, ancestors: number[]) => program.methods
+      .createTrixelAndAncestors({ id: new anchor.BN(id) })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(ancestors.map(trixelMeta))
+      .rpc();
+
+    // The tree stops at the bound root 15, so face 5 is never created
+    await createTrixel(115, [15]);
+    expect(await provider.connection.getAccountInfo(trixelMeta(5).pubkey)).to.be.null;
+    await program.methods.updateTrixel({ id: new anchor.BN(115), value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(115).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts([trixelMeta(15)]).rpc();
+
+    const root = await fetchTrixel(program, trixelMeta(15).pubkey);
+    // @ts-ignore
+    expect(root.data.count.count).to.equal(1);
+    const worldAccount = await program.account.world.fetch(worldKeypair.publicKey);
+    expect(Buffer.from(worldAccount.childHashes[0])).to.eql(Buffer.from(root.hash));
+
+    try {
+      await createTrixel(113, getTrixelAncestors(113));
+      expect.fail("a trixel outside the bounds should be rejected");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("OutOfBounds");
+    }
+
+    // Face 5 has no account, but holds data beneath it all the same
+    let rejected = false;
+    try {
+      await program.methods.getRegionValue({ ids: [new anchor.BN(5)] })
+        .accountsStrict({ world: worldKeypair.publicKey })
+        .remainingAccounts([trixelMeta(5)])
+        .view();
+    } catch (e: any) {
+      rejected = true;
+      expect(e.toString()).to.contain("OutOfBounds");
+    }
+    expect(rejected, "a trixel above the bound roots should be rejected").to.be.true;
+  });
+
+  it("should accept coarse writes to leaves of a variable-resolution world", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("VariableWorld".padEnd(32, "\0")));
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution: 2, dataType: { aggregateAccumulate: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const trixelMeta = (id: number): AccountMeta => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    });
+    const createTrixel = (id: number) => program.methods
+      .createTrixelAndAncestors({ id: new anchor.BN(id) })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts(getTrixelAncestors(id).map(trixelMeta))
+      .rpc();
+    const updateTrixel = (id: number, value: number) => program.methods
+      .updateTrixel({ id: new anchor.BN(id), value, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(getTrixelAncestors(id).map(trixelMeta))
+      .rpc();
+
+    // Coarse trixels are only accepted once the world opts in
+    try {
+      await createTrixel(16);
+      expect.fail("a coarse trixel should be rejected");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("InvalidResolution");
+    }
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: null, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: true })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    // A coarse leaf and a fine leaf under the same face both roll up into it
+    await createTrixel(16);
+    await updateTrixel(16, 10);
+    await createTrixel(126);
+    await updateTrixel(126, 5);
+    const face = await fetchTrixel(program, trixelMeta(6).pubkey);
+    // @ts-ignore
+    expect(face.data.aggregateAccumulate.metric.toNumber()).to.equal(15);
+
+    // The written coarse leaf can't gain descendants, and trixels with descendants can't be written
+    try {
+      await createTrixel(116);
+      expect.fail("a trixel beneath a written leaf should be rejected");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("ResolutionConflict");
+    }
+    try {
+      await updateTrixel(26, 1);
+      expect.fail("a write to a trixel with descendants should be rejected");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("ResolutionConflict");
+    }
+  });
+
+  it("should refine and coarsen a world's canonical resolution in batches", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("ResolutionWorld".padEnd(32, "\0")));
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution: 1, dataType: { aggregateAccumulate: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const trixelMeta = (id: number): AccountMeta => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    });
+    const withChildren = (id: number) => [id, ...[1, 2, 3, 4].map(digit => digit * 10 ** String(id).length + id)].map(trixelMeta);
+    const updateTrixel = (id: number, value: number) => program.methods
+      .updateTrixel({ id: new anchor.BN(id), value, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(getTrixelAncestors(id).map(trixelMeta))
+      .rpc();
+    const changeResolution = async (targetResolution: number) => {
+      await program.methods.startResolutionChange({ targetResolution, splitRule: { divide: {} } })
+        .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+        .rpc();
+      await program.methods.retargetTrixels({ ids: [new anchor.BN(15)] })
+        .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey, systemProgram: SystemProgram.programId })
+        .remainingAccounts(withChildren(15))
+        .rpc();
+      await program.methods.rehashTrixels({ ids: [new anchor.BN(5)] })
+        .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+        .remainingAccounts(withChildren(5))
+        .rpc();
+      await program.methods.finishResolutionChange()
+        .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+        .remainingAccounts([1, 2, 3, 4, 5, 6, 7, 8].map(trixelMeta))
+        .rpc();
+    };
+
+    await program.methods.createTrixelAndAncestors({ id: new anchor.BN(15) })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(15).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts([trixelMeta(5)]).rpc();
+    await updateTrixel(15, 10);
+    const rootHashBefore = (await program.account.world.fetch(worldKeypair.publicKey)).rootHash;
+
+    // Refining splits the leaf's 10 among its children
+    await changeResolution(2);
+    let worldAccount = await program.account.world.fetch(worldKeypair.publicKey);
+    expect(worldAccount.canonicalResolution).to.equal(2);
+    // @ts-ignore
+    expect(worldAccount.data.aggregateAccumulate.metric.toNumber()).to.equal(10);
+    expect(Buffer.from(worldAccount.rootHash)).to.not.eql(Buffer.from(rootHashBefore));
+    const shares = await Promise.all([115, 215, 315, 415].map(id => fetchTrixel(program, trixelMeta(id).pubkey)));
+    // @ts-ignore
+    expect(shares.map(share => share.data.aggregateAccumulate.metric.toNumber())).to.eql([3, 3, 2, 2]);
+    const face = await fetchTrixel(program, trixelMeta(5).pubkey);
+    expect(Buffer.from(worldAccount.childHashes[4])).to.eql(Buffer.from(face.hash));
+    await updateTrixel(115, 1);
+
+    // Coarsening merges them back and closes the children
+    await changeResolution(1);
+    worldAccount = await program.account.world.fetch(worldKeypair.publicKey);
+    expect(worldAccount.canonicalResolution).to.equal(1);
+    const merged = await fetchTrixel(program, trixelMeta(15).pubkey);
+    // @ts-ignore
+    expect(merged.data.aggregateAccumulate.metric.toNumber()).to.equal(11);
+    expect(await provider.connection.getAccountInfo(trixelMeta(115).pubkey)).to.be.null;
+    await updateTrixel(15, 1);
+  });
+
+  it("should keep parent totals when refining means and refuse to replicate sums", async () => {
+    const createWorld = async (name: string, dataType: any) => {
+      const worldKeypair = anchor.web3.Keypair.generate();
+      await program.methods
+        .createWorld({ name: Array.from(Buffer.from(name.padEnd(32, "\0"))), canonicalResolution: 1, dataType, permissionedUpdates: false })
+        .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+        .signers([worldKeypair]).rpc();
+      return worldKeypair.publicKey;
+    };
+
+    // Replicating a sum would count it once per child
+    const sumWorld = await createWorld("ReplicatedSumWorld", { aggregateAccumulate: {} });
+    let rejected = false;
+    try {
+      await program.methods.startResolutionChange({ targetResolution: 2, splitRule: { replicate: {} } })
+        .accountsStrict({ authority: provider.wallet.publicKey, world: sumWorld })
+        .rpc();
+    } catch (e: any) {
+      rejected = true;
+      expect(e.toString()).to.contain("InvalidArgument");
+    }
+    expect(rejected, "replicating a sum should be rejected").to.be.true;
+
+    const world = await createWorld("DividedMeanWorld", { meanAccumulate: {} });
+    const trixelMeta = (id: number): AccountMeta => ({
+      pubkey: getTrixelPDA(world, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    });
+    const withChildren = (id: number) => [id, ...[1, 2, 3, 4].map(digit => digit * 10 ** String(id).length + id)].map(trixelMeta);
+    await program.methods.createTrixelAndAncestors({ id: new anchor.BN(15) })
+      .accountsStrict({ world, trixel: trixelMeta(15).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts([trixelMeta(5)]).rpc();
+    for (const value of [10, 20, 6]) {
+      await program.methods.updateTrixel({ id: new anchor.BN(15), value, coords: null, expectedUpdates: null, expectedHash: null })
+        .accountsStrict({ world, trixel: trixelMeta(15).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+        .remainingAccounts([trixelMeta(5)]).rpc();
+    }
+    const totals = async (id: number) => {
+      // @ts-ignore
+      const { numerator, denominator } = (await fetchTrixel(program, trixelMeta(id).pubkey)).data.meanAccumulate;
+      return [numerator.toNumber(), denominator.toNumber()];
+    };
+    const before = await totals(5);
+    expect(before).to.eql([36, 1]);
+
+    await program.methods.startResolutionChange({ targetResolution: 2, splitRule: { divide: {} } })
+      .accountsStrict({ authority: provider.wallet.publicKey, world })
+      .rpc();
+    await program.methods.retargetTrixels({ ids: [new anchor.BN(15)] })
+      .accountsStrict({ authority: provider.wallet.publicKey, world, systemProgram: SystemProgram.programId })
+      .remainingAccounts(withChildren(15))
+      .rpc();
+    await program.methods.rehashTrixels({ ids: [new anchor.BN(5)] })
+      .accountsStrict({ authority: provider.wallet.publicKey, world })
+      .remainingAccounts(withChildren(5))
+      .rpc();
+    await program.methods.finishResolutionChange()
+      .accountsStrict({ authority: provider.wallet.publicKey, world })
+      .remainingAccounts([1, 2, 3, 4, 5, 6, 7, 8].map(trixelMeta))
+      .rpc();
+
+    // The leaf's one sample goes to its first child along with its numerator, so
+    // the totals hold rather than the numerator being spread over four samples
+    expect(await totals(15)).to.eql(before);
+    expect(await totals(5)).to.eql(before);
+    expect(await Promise.all([115, 215, 315, 415].map(totals))).to.eql([[36, 1], [0, 0], [0, 0], [0, 0]]);
+    const worldAccount = await program.account.world.fetch(world);
+    // @ts-ignore
+    expect([worldAccount.data.meanAccumulate.numerator.toNumber(), worldAccount.data.meanAccumulate.denominator.toNumber()]).to.eql(before);
+  });
+
+  it("should refine written leaves back at zero and keep owned or frozen children from being coarsened", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("RetargetGuardWorld".padEnd(32, "\0")));
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution: 1, dataType: { aggregateAccumulate: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+    await program.methods.updateWorld({ revealWindow: null, minUpdateInterval: null, maxUpdatesPerEpoch: null, paused: null, claimsEnabled: true, challengeWindow: null, disputeBond: null, arbiter: null, minStake: null, stakeMint: null, unbondingPeriod: null, variableResolution: null })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+
+    const trixelMeta = (id: number): AccountMeta => ({
+      pubkey: getTrixelPDA(worldKeypair.publicKey, id, program.programId)[0],
+      isSigner: false,
+      isWritable: true,
+    });
+    const withChildren = (id: number) => [id, ...[1, 2, 3, 4].map(digit => digit * 10 ** String(id).length + id)].map(trixelMeta);
+    const updateTrixel = (id: number, value: number) => program.methods
+      .updateTrixel({ id: new anchor.BN(id), value, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts(getTrixelAncestors(id).map(trixelMeta))
+      .rpc();
+    const startResolutionChange = (targetResolution: number) => program.methods
+      .startResolutionChange({ targetResolution, splitRule: { divide: {} } })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+      .rpc();
+    const retarget = () => program.methods.retargetTrixels({ ids: [new anchor.BN(15)] })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey, systemProgram: SystemProgram.programId })
+      .remainingAccounts(withChildren(15))
+      .rpc();
+    const finishResolutionChange = async () => {
+      await program.methods.rehashTrixels({ ids: [new anchor.BN(5)] })
+        .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+        .remainingAccounts(withChildren(5))
+        .rpc();
+      await program.methods.finishResolutionChange()
+        .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey })
+        .remainingAccounts([1, 2, 3, 4, 5, 6, 7, 8].map(trixelMeta))
+        .rpc();
+    };
+    const setFrozen = (id: number, frozen: boolean) => program.methods.setTrixelFrozen({ id: new anchor.BN(id), frozen })
+      .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: trixelMeta(id).pubkey })
+      .rpc();
+
+    // A leaf written back to zero still has updates, so it is split like any written leaf
+    await program.methods.createTrixelAndAncestors({ id: new anchor.BN(15) })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelMeta(15).pubkey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts([trixelMeta(5)]).rpc();
+    await updateTrixel(15, 0);
+    await startResolutionChange(2);
+    await retarget();
+    await finishResolutionChange();
+    for (const id of [115, 215, 315, 415]) {
+      expect(await provider.connection.getAccountInfo(trixelMeta(id).pubkey)).to.not.be.null;
+    }
+    await updateTrixel(115, 3);
+
+    // Closing a child would drop its owner, or its freeze
+    await program.methods.claimTrixel({ id: new anchor.BN(215) })
+      .accountsStrict({ payer: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: trixelMeta(215).pubkey, ownerTokenAccount: null })
+      .remainingAccounts(getTrixelAncestors(215).map(trixelMeta))
+      .rpc();
+    await setFrozen(315, true);
+    await startResolutionChange(1);
+
+    let rejected = false;
+    try {
+      await retarget();
+    } catch (e: any) {
+      rejected = true;
+      expect(e.toString()).to.contain("TrixelAlreadyOwned");
+    }
+    expect(rejected, "coarsening over an owned child should be rejected").to.be.true;
+    await program.methods.transferTrixel({ id: new anchor.BN(215), newOwner: null })
+      .accountsStrict({ owner: provider.wallet.publicKey, world: worldKeypair.publicKey, trixel: trixelMeta(215).pubkey })
+      .remainingAccounts(getTrixelAncestors(215).map(trixelMeta))
+      .rpc();
+
+    rejected = false;
+    try {
+      await retarget();
+    } catch (e: any) {
+      rejected = true;
+      expect(e.toString()).to.contain("TrixelFrozen");
+    }
+    expect(rejected, "coarsening over a frozen child should be rejected").to.be.true;
+    await setFrozen(315, false);
+
+    // Nor can an empty account stand in for a child to leave it behind
+    rejected = false;
+    try {
+      const accounts = withChildren(15);
+      accounts[2] = trixelMeta(16);
+      await program.methods.retargetTrixels({ ids: [new anchor.BN(15)] })
+        .accountsStrict({ authority: provider.wallet.publicKey, world: worldKeypair.publicKey, systemProgram: SystemProgram.programId })
+        .remainingAccounts(accounts)
+        .rpc();
+    } catch (e: any) {
+      rejected = true;
+      expect(e.toString()).to.contain("InvalidTrixelAccount");
+    }
+    expect(rejected, "an empty account other than the child's should be rejected").to.be.true;
+
+    await retarget();
+    await finishResolutionChange();
+    const merged = await fetchTrixel(program, trixelMeta(15).pubkey);
+    // @ts-ignore
+    expect(merged.data.aggregateAccumulate.metric.toNumber()).to.equal(3);
+    expect(await provider.connection.getAccountInfo(trixelMeta(215).pubkey)).to.be.null;
+  });
+
+  it("should hash trixels and worlds with their position and domain", async () => {
+    const worldKeypair = anchor.web3.Keypair.generate();
+    const worldNameArray = Array.from(Buffer.from("HashedWorld".padEnd(32, "\0")));
+    const canonicalResolution = 1;
+    await program.methods
+      .createWorld({ name: worldNameArray, canonicalResolution, dataType: { count: {} }, permissionedUpdates: false })
+      .accountsStrict({ world: worldKeypair.publicKey, authority: provider.wallet.publicKey, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([worldKeypair]).rpc();
+
+    const [trixelPda] = getTrixelPDA(worldKeypair.publicKey, 15, program.programId);
+    const [facePda] = getTrixelPDA(worldKeypair.publicKey, 5, program.programId);
+    await program.methods.createTrixelAndAncestors({ id: new anchor.BN(15) })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, fee: NO_FEE_ACCOUNTS })
+      .remainingAccounts([{ pubkey: facePda, isSigner: false, isWritable: true }]).rpc();
+    await program.methods.updateTrixel({ id: new anchor.BN(15), value: 1, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world: worldKeypair.publicKey, trixel: trixelPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts([{ pubkey: facePda, isSigner: false, isWritable: true }]).rpc();
+
+    // Count data is Borsh encoded as its variant index and a u32
+    const countData = (count: number) => Buffer.concat([Buffer.from([0]), new anchor.BN(count).toArrayLike(Buffer, "le", 4)]);
+    const trixelHash = (id: number, resolution: number, count: number, childHashes: Buffer[]) => createHash("sha256")
+      .update(Buffer.from("geovm:trixel:v1"))
+      .update(worldKeypair.publicKey.toBuffer())
+      .update(new anchor.BN(id).toArrayLike(Buffer, "le", 8))
+      .update(Buffer.from([resolution, 0]))
+      .update(countData(count))
+      .update(Buffer.concat(childHashes))
+      .digest();
+
+    const trixel = await fetchTrixel(program, trixelPda);
+    expect(trixel.hashVersion).to.equal(1);
+    const leafHash = trixelHash(15, 1, 1, [0, 1, 2, 3].map(() => Buffer.alloc(32)));
+    expect(Buffer.from(trixel.hash)).to.eql(leafHash);
+    const faceHash = trixelHash(5, 0, 1, [leafHash, Buffer.alloc(32), Buffer.alloc(32), Buffer.alloc(32)]);
+    expect(Buffer.from((await fetchTrixel(program, facePda)).hash)).to.eql(faceHash);
+
+    const worldAccount = await program.account.world.fetch(worldKeypair.publicKey);
+    const childHashes = [0, 1, 2, 3, 4, 5, 6, 7].map(i => i == 4 ? faceHash : Buffer.alloc(32));
+    const rootHash = createHash("sha256")
+      .update(Buffer.from("geovm:world:v1"))
+      .update(worldKeypair.publicKey.toBuffer())
+      .update(Buffer.from([canonicalResolution, 0, 0]))
+      .update(Buffer.alloc(8 * 8))
+      .update(countData(1))
+      .update(Buffer.concat(childHashes))
+      .digest();
+    expect(Buffer.from(worldAccount.rootHash)).to.eql(rootHash);
+  });
+
+  it("should migrate a world created before its later fields to the current layout", async () => {
+    // Loaded by the test validator in the layout and size worlds had before, see Anchor.toml
+    const authority = getLegacyFixtureKeypair("authority");
+    const world = getLegacyFixtureKeypair("world").publicKey;
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(authority.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const before = await provider.connection.getAccountInfo(world);
+    expect(before.data.length).to.equal(450);
+    let rejected = false;
+    try {
+      await program.account.world.fetch(world);
+    } catch (e: any) {
+      rejected = true;
+    }
+    expect(rejected, "a legacy world should not deserialize").to.be.true;
+
+    // Only the world's authority can migrate it
+    try {
+      await program.methods.migrateWorld()
+        .accountsStrict({ authority: provider.wallet.publicKey, world, systemProgram: SystemProgram.programId })
+        .rpc();
+      expect.fail("migration by another signer should fail");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("UnauthorizedAction");
+    }
+
+    await program.methods.migrateWorld()
+      .accountsStrict({ authority: authority.publicKey, world, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+
+    const after = await provider.connection.getAccountInfo(world);
+    expect(after.data.length).to.be.greaterThan(before.data.length);
+    expect(after.lamports).to.equal(await provider.connection.getMinimumBalanceForRentExemption(after.data.length));
+    const worldAccount = await program.account.world.fetch(world);
+    expect(worldAccount.authority).to.eql(authority.publicKey);
+    expect(Buffer.from(worldAccount.name).toString().replace(/\0+$/, "")).to.equal("LegacyWorld");
+    expect(worldAccount.canonicalResolution).to.equal(1);
+    expect(worldAccount.updates.toNumber()).to.equal(1);
+    expect(Buffer.from(worldAccount.rootHash)).to.eql(before.data.subarray(8 + 32 + 32 + 2 + 8, 8 + 32 + 32 + 2 + 8 + 32));
+    // @ts-ignore
+    expect(worldAccount.data.aggregateOverwrite.metric.toNumber()).to.equal(5);
+    expect(worldAccount.feeSchedule).to.be.null;
+    expect(worldAccount.paused).to.be.false;
+
+    // It keeps the legacy hash scheme, so recomputing the root hash leaves it as it was
+    expect(worldAccount.hashVersion).to.equal(0);
+    await program.methods.setWorldBounds({ roots: [], rooted: false })
+      .accountsStrict({ authority: authority.publicKey, world })
+      .signers([authority])
+      .rpc();
+    const rehashed = await program.account.world.fetch(world);
+    expect(Buffer.from(rehashed.rootHash)).to.eql(Buffer.from(worldAccount.rootHash));
+
+    // Once migrated, it is no longer in the legacy layout
+    try {
+      await program.methods.migrateWorld()
+        .accountsStrict({ authority: authority.publicKey, world, systemProgram: SystemProgram.programId })
+        .signers([authority])
+        .rpc();
+      expect.fail("a migrated world should not migrate again");
+    } catch (e: any) {
+      expect(e.error?.errorCode?.code).to.equal("InvalidAccount");
+    }
+  });
+
+  it("should migrate trixels created before they were zero-copy", async () => {
+    // Loaded by the test validator in the Borsh layout, under the world migrated above
+    const authority = getLegacyFixtureKeypair("authority");
+    const world = getLegacyFixtureKeypair("world").publicKey;
+    const hashOffset = 8 + 32 + 8 + 1 + 8 + 8;
+    const [leafPda] = getTrixelPDA(world, 12, program.programId);
+    const [facePda] = getTrixelPDA(world, 2, program.programId);
+    const writeLeaf = (value: number) => program.methods
+      .updateTrixel({ id: new anchor.BN(12), value, coords: null, expectedUpdates: null, expectedHash: null })
+      .accountsStrict({ world, trixel: leafPda, payer: provider.wallet.publicKey, systemProgram: SystemProgram.programId, write: NO_WRITE_ACCOUNTS })
+      .remainingAccounts([{ pubkey: facePda, isSigner: false, isWritable: true }])
+      .rpc({ commitment: "confirmed" });
+
+    // Legacy trixels keep the old discriminator, so they can't be read as zero-copy
+    let rejected = false;
+    try {
+      await writeLeaf(9);
+    } catch (e: any) {
+      rejected = true;
+      expect(e.error?.errorCode?.code).to.equal("AccountDiscriminatorMismatch");
+    }
+    expect(rejected, "a write to a legacy trixel should fail").to.be.true;
+
+    for (const id of [2, 12]) {
+      const [trixelPda, bump] = getTrixelPDA(world, id, program.programId);
+      const before = await provider.connection.getAccountInfo(trixelPda);
+      expect(before.data.length).to.equal(306);
+      const authorityBalance = await provider.connection.getBalance(authority.publicKey);
+
+      await program.methods.migrateTrixel({ id: new anchor.BN(id) })
+        .accountsStrict({ authority: authority.publicKey, world, trixel: trixelPda })
+        .signers([authority])
+        .rpc();
+
+      // The account shrinks to the zero-copy size and its freed rent goes to the authority
+      const after = await provider.connection.getAccountInfo(trixelPda);
+      expect(after.data.length).to.equal(8 + 280);
+      expect(after.lamports).to.equal(await provider.connection.getMinimumBalanceForRentExemption(after.data.length));
+      expect(await provider.connection.getBalance(authority.publicKey)).to.equal(authorityBalance + before.lamports - after.lamports);
+
+      const trixel = await fetchTrixel(program, trixelPda);
+      expect(trixel.id.toNumber()).to.equal(id);
+      expect(trixel.bump).to.equal(bump);
+      expect(trixel.hashVersion).to.equal(0);
+      expect(trixel.updates.toNumber()).to.equal(1);
+      // @ts-ignore
+      expect(trixel.data.aggregateOverwrite.metric.toNumber()).to.equal(5);
+      expect(Buffer.from(trixel.hash)).to.eql(before.data.subarray(hashOffset, hashOffset + 32));
+      expect(trixel.owner).to.be.null;
+      expect(trixel.frozen).to.be.false;
+
+      try {
+        await program.methods.migrateTrixel({ id: new anchor.BN(id) })
+          .accountsStrict({ authority: authority.publicKey, world, trixel: trixelPda })
+          .signers([authority])
+          .rpc();
+        expect.fail("a migrated trixel should not migrate again");
+      } catch (e: any) {
+        expect(e.error?.errorCode?.code).to.equal("InvalidAccount");
+      }
+    }
+
+    // Migrated trixels take writes, still hashed the legacy way
+    await writeLeaf(9);
+    const leaf = await fetchTrixel(program, leafPda);
+    const data = Buffer.alloc(9);
+    data.writeUInt8(1);
+    data.writeBigUInt64LE(BigInt(9), 1);
+    expect(Buffer.from(leaf.hash)).to.eql(createHash("sha256").update(data).update(Buffer.alloc(4 * 32)).digest());
+    const worldAccount = await program.account.world.fetch(world);
+    expect(worldAccount.updates.toNumber()).to.equal(2);
+    expect(Buffer.from(worldAccount.rootHash)).to.eql(
+      createHash("sha256").update(Buffer.concat(worldAccount.childHashes.map(hash => Buffer.from(hash)))).digest()
+    );
+  });
 });
@@ -1,5 +1,7 @@
-import { PublicKey } from "@solana/web3.js";
-import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { BN, Program } from "@coral-xyz/anchor";
+import { createHash } from "crypto";
+import { Geovm } from "../target/types/geovm";
 
 // Types
 export interface SphericalCoords {
@@ -16,6 +18,34 @@ export interface Vector3D {
 // Constants
 const PI = Math.PI;
 
+// Fee accounts to pass for worlds without a fee schedule
+export const NO_FEE_ACCOUNTS = {
+    treasury: null,
+    feeMint: null,
+    payerTokenAccount: null,
+    treasuryTokenAccount: null,
+    tokenProgram: null,
+};
+
+// Reporter accounts to pass for writes that claim no bounty
+export const NO_REPORTER_ACCOUNTS = {
+    record: null,
+    bounty: null,
+    bountyMint: null,
+    bountyTokenAccount: null,
+    reporterTokenAccount: null,
+    tokenProgram: null,
+};
+
+// Write accounts to pass for plain writes to worlds without stakes, fees or a challenge window
+export const NO_WRITE_ACCOUNTS = {
+    stake: null,
+    ownerTokenAccount: null,
+    updateRecord: null,
+    fee: NO_FEE_ACCOUNTS,
+    reporter: NO_REPORTER_ACCOUNTS,
+};
+
 // Initial octahedron vertices
 const V_OCT: Vector3D[] = [
     { x: 0.0, y: 0.0, z: 1.0 },   // v0 (North Pole)
@@ -174,12 +204,21 @@ export function getHtmId(coords: SphericalCoords, depth: number): number {
     return currentHtmId;
 }
 
+// Convert an HTM ID to a trixel ID by reversing its digits. getHtmId lists the
+// base triangle first, trixel IDs list it last so ancestors drop the leading digit
+export function trixelIdFromHtmId(htmId: number): number {
+    return parseInt(htmId.toString().split("").reverse().join(""));
+}
+
 // Get trixel ID from coordinates and resolution
+//
+// Note: this used to return getHtmId's base-first order unchanged, which only
+// matched trixel IDs at resolution 0
 export function getTrixelId(coords: SphericalCoords, resolution: number): number {
     if (resolution > 31) {
         throw new Error("Invalid resolution");
     }
-    return getHtmId(coords, resolution);
+    return trixelIdFromHtmId(getHtmId(coords, resolution));
 }
 
 // Get ancestors for trixel ID
@@ -227,6 +266,23 @@ export function getTrixelPDA(world: PublicKey, trixelId: number, programId: Publ
     );
 }
 
+// Get the PDA of a world created with create_named_world
+export function getWorldPDA(authority: PublicKey, name: string, programId: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [
+            Buffer.from("world"),
+            authority.toBuffer(),
+            Buffer.from(name.padEnd(32, "\0"))
+        ],
+        programId
+    );
+}
+
+// Get the keypair behind a key of the legacy fixtures, see tests/fixtures/generate-legacy-fixtures.js
+export function getLegacyFixtureKeypair(name: string): Keypair {
+    return Keypair.fromSeed(createHash("sha256").update(`geovm:legacy-fixture:${name}`).digest());
+}
+
 // Get resolution from trixel ID
 export function getResolutionFromTrixelId(id: number): number {
     if (id >= 1 && id <= 8) {
@@ -264,4 +320,28 @@ export function getTrixelAndAncestorPDAs(
         trixelPda,
         ancestorPDAs
     };
-} 
\ No newline at end of file
+}
+
+// Unpacks a zero-copy trixel's data into the TrixelData shape worlds hold
+export function unpackTrixelData(packed: { values: BN[], tag: number }): any {
+    const [first, second] = packed.values;
+    switch (packed.tag) {
+        case 0: return { count: { count: first.toNumber() } };
+        case 1: return { aggregateOverwrite: { metric: first } };
+        case 2: return { aggregateAccumulate: { metric: first } };
+        case 3: return { meanOverwrite: { numerator: first, denominator: second } };
+        case 4: return { meanAccumulate: { numerator: first, denominator: second } };
+        default: throw new Error("Invalid trixel data tag");
+    }
+}
+
+// Fetches a trixel with its data unpacked, its owner null when unowned and frozen as a boolean
+export async function fetchTrixel(program: Program<Geovm>, address: PublicKey) {
+    const trixel = await program.account.trixel.fetch(address);
+    return {
+        ...trixel,
+        data: unpackTrixelData(trixel.data),
+        owner: trixel.owner.equals(PublicKey.default) ? null : trixel.owner,
+        frozen: trixel.frozen !== 0,
+    };
+}